@@ -16,7 +16,6 @@ where
     true
 }
 
-#[allow(dead_code)]
 pub fn contains_all<T, I>(haystack: &HashSet<T>, needles: I) -> bool
 where
     T: Eq + std::hash::Hash,
@@ -30,16 +29,10 @@ where
     true
 }
 
-#[allow(dead_code)]
 pub fn get_first_not_in_set<T, I>(haystack: &HashSet<T>, needles: I) -> Option<T>
 where
     T: Eq + std::hash::Hash,
     I: IntoIterator<Item = T>,
 {
-    for n in needles {
-        if !haystack.contains(&n) {
-            return Some(n);
-        }
-    }
-    None
+    needles.into_iter().find(|n| !haystack.contains(n))
 }
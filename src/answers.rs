@@ -0,0 +1,197 @@
+use runner::DayResult;
+use solver::{Answer, Part};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use toml;
+
+/// File name of the answers manifest inside the input directory
+pub const MANIFEST: &str = "answers.toml";
+
+/// Expected answers, keyed by day name ("day3") and part
+pub struct Manifest {
+    answers: HashMap<(String, u32), String>,
+}
+
+impl Manifest {
+    pub fn load<P>(filename: P) -> Result<Manifest, String>
+    where
+        P: AsRef<Path>,
+    {
+        let content = fs::read_to_string(&filename)
+            .map_err(|e| format!("{}: {}", filename.as_ref().display(), e))?;
+        Manifest::parse(&content).map_err(|e| format!("{}: {}", filename.as_ref().display(), e))
+    }
+
+    /// Parses a manifest of the form
+    /// ```toml
+    /// [day1]
+    /// part1 = 67450
+    /// part2 = "some text"
+    /// ```
+    pub fn parse(content: &str) -> Result<Manifest, String> {
+        let table: toml::Table = content.parse().map_err(|e| format!("{}", e))?;
+        let mut answers = HashMap::new();
+        for (day, parts) in table.iter() {
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("'{}' must be a table of parts", day))?;
+            for (part, value) in parts.iter() {
+                let number = part
+                    .strip_prefix("part")
+                    .and_then(|n| n.parse::<u32>().ok())
+                    .ok_or_else(|| format!("Invalid part '{}' in '{}'", part, day))?;
+                let expected = match value {
+                    toml::Value::Integer(n) => n.to_string(),
+                    toml::Value::String(s) => s.clone(),
+                    _ => return Err(format!("Unsupported answer type for {}.{}", day, part)),
+                };
+                answers.insert((day.clone(), number), expected);
+            }
+        }
+        Ok(Manifest { answers })
+    }
+
+    pub fn expected(&self, day: &str, part: Part) -> Option<&str> {
+        self.answers
+            .get(&(day.to_string(), part.number()))
+            .map(|s| s.as_str())
+    }
+
+    /// Compares the answers of a day against the manifest
+    pub fn check(&self, result: &DayResult) -> Vec<Verdict> {
+        result
+            .parts
+            .iter()
+            .zip(Part::iter())
+            .map(|(part_result, part)| {
+                let status = match (self.expected(&result.name, *part), &part_result.answer) {
+                    (_, Err(msg)) => Status::Failed(msg.clone()),
+                    (None, Ok(_)) => Status::Unchecked,
+                    (Some(expected), Ok(actual)) => compare(expected, actual),
+                };
+                Verdict {
+                    day: result.name.clone(),
+                    part: *part,
+                    status,
+                }
+            })
+            .collect()
+    }
+}
+
+fn compare(expected: &str, actual: &Answer) -> Status {
+    let actual = actual.to_string();
+    if expected == actual {
+        Status::Match
+    } else {
+        Status::Mismatch {
+            expected: expected.to_string(),
+            actual,
+        }
+    }
+}
+
+pub enum Status {
+    Match,
+    Mismatch { expected: String, actual: String },
+    Unchecked,
+    Failed(String),
+}
+
+/// Result of checking one part against the manifest
+pub struct Verdict {
+    pub day: String,
+    pub part: Part,
+    pub status: Status,
+}
+
+impl Verdict {
+    /// Whether the answer matches the manifest. A part without an expected answer is not
+    /// ok, so that a check cannot pass for answers it never compared.
+    pub fn is_ok(&self) -> bool {
+        matches!(self.status, Status::Match)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: ", self.day, self.part)?;
+        match &self.status {
+            Status::Match => write!(f, "ok"),
+            Status::Unchecked => write!(f, "UNCHECKED: no expected answer"),
+            Status::Failed(msg) => write!(f, "FAILED: {}", msg),
+            Status::Mismatch { expected, actual } => {
+                writeln!(f, "MISMATCH")?;
+                for line in expected.lines() {
+                    writeln!(f, "- {}", line)?;
+                }
+                let mut lines = actual.lines().peekable();
+                while let Some(line) = lines.next() {
+                    write!(f, "+ {}", line)?;
+                    if lines.peek().is_some() {
+                        writeln!(f)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runner::PartResult;
+    use std::time::Duration;
+
+    fn day_result(name: &str, answers: Vec<Result<Answer, String>>) -> DayResult {
+        DayResult {
+            day: 0,
+            name: name.to_string(),
+            parts: answers
+                .into_iter()
+                .map(|answer| PartResult {
+                    answer,
+                    elapsed: Duration::default(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let manifest = Manifest::parse("[day1]\npart1 = 12\npart2 = '''\nab\ncd'''\n").unwrap();
+        assert_eq!(Some("12"), manifest.expected("day1", Part::One));
+        assert_eq!(Some("ab\ncd"), manifest.expected("day1", Part::Two));
+        assert_eq!(None, manifest.expected("day2", Part::One));
+
+        assert!(Manifest::parse("[day1]\npart3x = 1\n").is_err());
+        assert!(Manifest::parse("day1 = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let manifest = Manifest::parse("[day1]\npart1 = 12\npart2 = \"AB\"\n").unwrap();
+
+        let verdicts = manifest.check(&day_result(
+            "day1",
+            vec![Ok(Answer::UInt(12)), Ok(Answer::from("AC"))],
+        ));
+        assert!(verdicts[0].is_ok());
+        assert!(!verdicts[1].is_ok());
+        assert_eq!("day1 part2: MISMATCH\n- AB\n+ AC", verdicts[1].to_string());
+
+        let verdicts = manifest.check(&day_result(
+            "day2",
+            vec![Ok(Answer::Int(1)), Err("boom".to_string())],
+        ));
+        assert!(!verdicts[0].is_ok());
+        assert_eq!(
+            "day2 part1: UNCHECKED: no expected answer",
+            verdicts[0].to_string()
+        );
+        assert!(!verdicts[1].is_ok());
+    }
+}
@@ -0,0 +1,293 @@
+use io::Input;
+use runner::{self, format_duration};
+use solver::{Part, Solver};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use toml;
+
+/// Default number of measured runs per part
+pub const RUNS: usize = 10;
+/// Default number of unmeasured runs before measuring
+pub const WARMUP: usize = 2;
+/// Default slowdown of the median, in percent, that counts as a regression
+pub const THRESHOLD: f64 = 10.0;
+
+/// How often to solve each part
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub warmup: usize,
+    pub runs: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            warmup: WARMUP,
+            runs: RUNS,
+        }
+    }
+}
+
+/// Timing statistics over repeated runs of one part
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Computes the statistics of a non-empty list of samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let mid = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 0 {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        let secs: Vec<f64> = sorted.iter().map(|d| d.as_secs_f64()).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / secs.len() as f64;
+        Some(Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Statistics of both parts of a day, in the order of `Part::iter`
+pub struct DayBench {
+    pub name: String,
+    pub parts: Vec<Result<Stats, String>>,
+}
+
+/// Solves one part `config.warmup + config.runs` times and summarizes the measured runs.
+/// Stops at the first failing run.
+pub fn bench_part(
+    solver: &dyn Solver,
+    part: Part,
+    input: &Input,
+    config: Config,
+) -> Result<Stats, String> {
+    let mut samples: Vec<Duration> = Vec::with_capacity(config.runs);
+    for run in 0..config.warmup + config.runs {
+        let result = runner::run_part(solver, part, input);
+        result.answer?;
+        if run >= config.warmup {
+            samples.push(result.elapsed);
+        }
+    }
+    Stats::from_samples(&samples).ok_or_else(|| "no measured runs".to_string())
+}
+
+pub fn bench_day(solver: &dyn Solver, input: &Input, config: Config) -> DayBench {
+    DayBench {
+        name: solver.name(),
+        parts: Part::iter()
+            .map(|part| bench_part(solver, *part, input, config))
+            .collect(),
+    }
+}
+
+/// Median times of a previous benchmark, keyed by day name ("day3") and part
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl Baseline {
+    pub fn from_results(results: &[DayBench]) -> Baseline {
+        let mut baseline = Baseline::default();
+        for result in results {
+            for (stats, part) in result.parts.iter().zip(Part::iter()) {
+                if let Ok(stats) = stats {
+                    baseline
+                        .medians
+                        .entry(result.name.clone())
+                        .or_default()
+                        .insert(part.to_string(), stats.median.as_secs_f64() * 1000.0);
+                }
+            }
+        }
+        baseline
+    }
+
+    pub fn load<P>(filename: P) -> Result<Baseline, String>
+    where
+        P: AsRef<Path>,
+    {
+        let content = fs::read_to_string(&filename)
+            .map_err(|e| format!("{}: {}", filename.as_ref().display(), e))?;
+        Baseline::parse(&content).map_err(|e| format!("{}: {}", filename.as_ref().display(), e))
+    }
+
+    pub fn save<P>(&self, filename: P) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(&filename, self.to_string())
+            .map_err(|e| format!("{}: {}", filename.as_ref().display(), e))
+    }
+
+    /// Parses a baseline of the form
+    /// ```toml
+    /// [day11]
+    /// part1 = 0.412
+    /// part2 = 95.3
+    /// ```
+    /// holding the median times in milliseconds
+    pub fn parse(content: &str) -> Result<Baseline, String> {
+        let table: toml::Table = content.parse().map_err(|e| format!("{}", e))?;
+        let mut baseline = Baseline::default();
+        for (day, parts) in table.iter() {
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("'{}' must be a table of parts", day))?;
+            for (part, value) in parts.iter() {
+                let ms = match value {
+                    toml::Value::Float(ms) => *ms,
+                    toml::Value::Integer(ms) => *ms as f64,
+                    _ => return Err(format!("Median of {}.{} must be a number", day, part)),
+                };
+                baseline
+                    .medians
+                    .entry(day.clone())
+                    .or_default()
+                    .insert(part.clone(), ms);
+            }
+        }
+        Ok(baseline)
+    }
+
+    /// Median time in milliseconds recorded for a part
+    pub fn median_ms(&self, day: &str, part: Part) -> Option<f64> {
+        self.medians.get(day)?.get(&part.to_string()).copied()
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (day, parts)) in self.medians.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{}]", day)?;
+            for (part, ms) in parts {
+                writeln!(f, "{} = {:.6}", part, ms)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Change of a part's median against the baseline
+pub struct Comparison {
+    pub day: String,
+    pub part: Part,
+    pub baseline_ms: f64,
+    pub current_ms: f64,
+}
+
+impl Comparison {
+    /// Relative change in percent, positive when slower
+    pub fn change(&self) -> f64 {
+        (self.current_ms - self.baseline_ms) / self.baseline_ms * 100.0
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+/// Compares every part that has a baseline median
+pub fn compare(results: &[DayBench], baseline: &Baseline) -> Vec<Comparison> {
+    let mut comparisons = Vec::new();
+    for result in results {
+        for (stats, part) in result.parts.iter().zip(Part::iter()) {
+            if let (Ok(stats), Some(baseline_ms)) = (stats, baseline.median_ms(&result.name, *part))
+            {
+                comparisons.push(Comparison {
+                    day: result.name.clone(),
+                    part: *part,
+                    baseline_ms,
+                    current_ms: stats.median.as_secs_f64() * 1000.0,
+                });
+            }
+        }
+    }
+    comparisons
+}
+
+/// Renders the statistics as a table with one row per part
+pub fn stats_table(results: &[DayBench]) -> String {
+    let mut rows: Vec<Vec<String>> = vec![["Day", "Part", "Min", "Median", "Mean", "Stddev"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()];
+    for result in results {
+        for (stats, part) in result.parts.iter().zip(Part::iter()) {
+            let mut row = vec![result.name.clone(), part.to_string()];
+            match stats {
+                Ok(stats) => row.extend(
+                    [stats.min, stats.median, stats.mean, stats.stddev]
+                        .iter()
+                        .map(|d| format_duration(*d)),
+                ),
+                Err(msg) => row.push(format!("FAILED: {}", msg)),
+            }
+            rows.push(row);
+        }
+    }
+
+    runner::render_table(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&[ms(4), ms(2), ms(8), ms(6)]).unwrap();
+        assert_eq!(ms(2), stats.min);
+        assert_eq!(ms(5), stats.median);
+        assert_eq!(5.0, (stats.mean.as_secs_f64() * 1000.0).round());
+        assert_eq!(2236, stats.stddev.as_micros());
+        assert_eq!(None, Stats::from_samples(&[]));
+    }
+
+    #[test]
+    fn test_baseline_roundtrip_and_regressions() {
+        let stats = Stats::from_samples(&[ms(10)]).unwrap();
+        let results = vec![DayBench {
+            name: "day1".to_string(),
+            parts: vec![Ok(stats), Err("boom".to_string())],
+        }];
+        let baseline = Baseline::from_results(&results);
+        assert_eq!("[day1]\npart1 = 10.000000\n", baseline.to_string());
+        assert_eq!(baseline, Baseline::parse(&baseline.to_string()).unwrap());
+
+        let faster = Baseline::parse("[day1]\npart1 = 8\npart2 = 1\n").unwrap();
+        let comparisons = compare(&results, &faster);
+        assert_eq!(1, comparisons.len());
+        assert_eq!(25.0, comparisons[0].change());
+        assert!(comparisons[0].is_regression(THRESHOLD));
+        assert!(!comparisons[0].is_regression(30.0));
+    }
+}
@@ -1,41 +1,101 @@
-use io;
-use std::path::Path;
+use error::{Error, Result};
+use io::{self, Input};
+use solver::{Answer, Part, Solver};
 
-pub fn day1(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day1, which is a valid file path.");
+pub struct Day1;
+
+impl Solver for Day1 {
+    fn day(&self) -> u32 {
+        1
     }
 
-    let mut calories_of_elf = get_calories(&args[0]);
+    fn input(&self) -> &'static str {
+        "calories.txt"
+    }
 
-    calories_of_elf.sort_by(|a, b| b.cmp(a));
+    fn part1(&self, input: &Input) -> Result<Answer> {
+        Ok(top_calories(input, 1)?[0].into())
+    }
 
-    println!("Hungriest Elf carries {} calories.", calories_of_elf[0]);
+    fn part2(&self, input: &Input) -> Result<Answer> {
+        let sum_top_3: i32 = top_calories(input, 3)?.iter().sum();
+        Ok(sum_top_3.into())
+    }
 
-    let sum_top_3: i32 = calories_of_elf[..3].iter().sum();
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("Hungriest Elf carries {} calories.", answer),
+            Part::Two => format!("The top 3 Elfs carry {answer} kcal in total."),
+        }
+    }
+}
 
-    println!("The top 3 Elfs carry {sum_top_3} kcal in total.");
+/// Calories carried by the `count` hungriest elves, hungriest first
+fn top_calories(input: &Input, count: usize) -> Result<Vec<i32>> {
+    let mut calories_of_elf = get_calories(input)?;
+    if calories_of_elf.len() < count {
+        return Err(Error::not_found(format!(
+            "{} elves, the input only lists {}",
+            count,
+            calories_of_elf.len()
+        )));
+    }
+    calories_of_elf.sort_by(|a, b| b.cmp(a));
+    calories_of_elf.truncate(count);
+    Ok(calories_of_elf)
 }
 
-fn get_calories<P>(filename: P) -> Vec<i32>
-where
-    P: AsRef<Path>,
-{
+fn get_calories(input: &Input) -> Result<Vec<i32>> {
     let mut calories_of_elf: Vec<i32> = Vec::new();
     let mut cur_calories = 0;
 
-    if let Ok(lines) = io::read_lines(filename) {
-        for line in lines {
-            if let Ok(line) = line {
-                if line.is_empty() {
-                    calories_of_elf.push(cur_calories);
-                    cur_calories = 0;
-                } else {
-                    let calories: i32 = line.parse().expect("Could not parse number");
-                    cur_calories += calories;
-                }
-            }
+    io::foreach_line(input, |line| {
+        if line.is_empty() {
+            calories_of_elf.push(cur_calories);
+            cur_calories = 0;
+        } else {
+            let calories: i32 = line.parse()?;
+            cur_calories += calories;
         }
+        Ok(())
+    })?;
+    // the last elf is not necessarily followed by an empty line
+    if cur_calories > 0 {
+        calories_of_elf.push(cur_calories);
+    }
+    Ok(calories_of_elf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    #[test]
+    fn test_example() {
+        let input = Input::text(EXAMPLE);
+        assert_eq!(Answer::Int(24000), Day1.part1(&input).unwrap());
+        assert_eq!(Answer::Int(45000), Day1.part2(&input).unwrap());
+    }
+
+    #[test]
+    fn test_get_calories() {
+        let input = Input::text(EXAMPLE);
+        assert_eq!(
+            vec![6000, 4000, 11000, 24000, 10000],
+            get_calories(&input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_too_few_elves() {
+        let input = Input::text("1000\n\n2000\n");
+        assert_eq!(Answer::Int(2000), Day1.part1(&input).unwrap());
+        assert_eq!(
+            "Not found: 3 elves, the input only lists 2",
+            Day1.part2(&input).unwrap_err().to_string()
+        );
+        assert!(Day1.part1(&Input::text("")).is_err());
     }
-    return calories_of_elf;
 }
@@ -0,0 +1,383 @@
+use error::{Error, Result};
+use io::{self, Input};
+use std::fmt;
+
+/// Cycles after which `Cpu::run` gives up, as the program most likely loops forever
+pub const CYCLE_LIMIT: usize = 1_000_000;
+
+/// One of the 26 registers, named `a` to `z`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Register(u8);
+
+impl Register {
+    /// The register the puzzle knows about, which starts at 1
+    pub const X: Register = Register(b'x' - b'a');
+
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.as_bytes() {
+            [letter] if letter.is_ascii_lowercase() => Ok(Register(letter - b'a')),
+            _ => Err(Error::parse(format!("Invalid register: {}", name))),
+        }
+    }
+
+    /// Every register, from `a` to `z`
+    pub fn all() -> impl Iterator<Item = Register> {
+        (0..26).map(Register)
+    }
+
+    pub fn name(self) -> char {
+        (b'a' + self.0) as char
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A single instruction of the handheld's CPU. Jump offsets are relative to the jump.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    Noop,
+    /// adds to the register, `x` unless the program names another one
+    Addx(Register, i32),
+    /// multiplies the register, `x` unless the program names another one
+    Mulx(Register, i32),
+    Jmp(i32),
+    /// jumps if the register is not zero
+    Jnz(Register, i32),
+    Push(Register),
+    Pop(Register),
+    Halt,
+}
+
+impl Instruction {
+    /// Cycles the instruction takes. Its effect applies at the end of the last one.
+    pub fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_, _) => 2,
+            Instruction::Mulx(_, _) => 3,
+            Instruction::Jmp(_) => 1,
+            Instruction::Jnz(_, _) => 2,
+            Instruction::Push(_) | Instruction::Pop(_) => 1,
+            Instruction::Halt => 1,
+        }
+    }
+}
+
+/// Formats the instruction as in the program, e.g. `jnz a -2`, leaving out the register
+/// of arithmetic on `x`
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::Addx(Register::X, value) => write!(f, "addx {}", value),
+            Instruction::Addx(register, value) => write!(f, "addx {} {}", register, value),
+            Instruction::Mulx(Register::X, value) => write!(f, "mulx {}", value),
+            Instruction::Mulx(register, value) => write!(f, "mulx {} {}", register, value),
+            Instruction::Jmp(offset) => write!(f, "jmp {}", offset),
+            Instruction::Jnz(register, offset) => write!(f, "jnz {} {}", register, offset),
+            Instruction::Push(register) => write!(f, "push {}", register),
+            Instruction::Pop(register) => write!(f, "pop {}", register),
+            Instruction::Halt => write!(f, "halt"),
+        }
+    }
+}
+
+/// Parses one line of the program, e.g. `addx -3` or `addx b 3`
+pub fn parse_instruction(line: &str) -> Result<Instruction> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    match parts[..] {
+        ["noop"] => Ok(Instruction::Noop),
+        ["addx", val] => Ok(Instruction::Addx(Register::X, val.parse::<i32>()?)),
+        ["addx", register, val] => Ok(Instruction::Addx(
+            Register::from_name(register)?,
+            val.parse::<i32>()?,
+        )),
+        ["mulx", val] => Ok(Instruction::Mulx(Register::X, val.parse::<i32>()?)),
+        ["mulx", register, val] => Ok(Instruction::Mulx(
+            Register::from_name(register)?,
+            val.parse::<i32>()?,
+        )),
+        ["jmp", offset] => Ok(Instruction::Jmp(offset.parse::<i32>()?)),
+        ["jnz", register, offset] => Ok(Instruction::Jnz(
+            Register::from_name(register)?,
+            offset.parse::<i32>()?,
+        )),
+        ["push", register] => Ok(Instruction::Push(Register::from_name(register)?)),
+        ["pop", register] => Ok(Instruction::Pop(Register::from_name(register)?)),
+        ["halt"] => Ok(Instruction::Halt),
+        _ => Err(Error::parse(format!("Unrecognized command: {}", line))),
+    }
+}
+
+/// Parses a program with one instruction per line
+pub fn parse_program(input: &Input) -> Result<Vec<Instruction>> {
+    let mut program = Vec::new();
+    io::foreach_nonempty_line(input, |line| {
+        program.push(parse_instruction(line)?);
+        Ok(())
+    })?;
+    Ok(program)
+}
+
+/// The handheld's CPU, executing a program cycle by cycle. Between cycles, the registers
+/// hold the values they have during the next cycle. The program halts with `halt` or
+/// by running past its last instruction.
+#[derive(Debug, Clone)]
+pub struct Cpu {
+    program: Vec<Instruction>,
+    registers: [i64; 26],
+    stack: Vec<i64>,
+    pc: usize,
+    cycle: usize,
+    /// cycles of the current instruction that already completed
+    elapsed: usize,
+    halted: bool,
+}
+
+impl Cpu {
+    /// A CPU at the start of the program, with `x` at 1 and every other register at 0
+    pub fn new(program: Vec<Instruction>) -> Self {
+        let mut registers = [0; 26];
+        registers[Register::X.0 as usize] = 1;
+        Cpu {
+            halted: program.is_empty(),
+            program,
+            registers,
+            stack: Vec::new(),
+            pc: 0,
+            cycle: 1,
+            elapsed: 0,
+        }
+    }
+
+    pub fn load(input: &Input) -> Result<Self> {
+        parse_program(input).map(Cpu::new)
+    }
+
+    /// Number of the next cycle, starting at 1
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    /// Index of the current instruction
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The instruction the next cycle works on, unless the program halted
+    pub fn current(&self) -> Option<Instruction> {
+        if self.halted {
+            None
+        } else {
+            Some(self.program[self.pc])
+        }
+    }
+
+    /// Cycles of the current instruction that already completed
+    pub fn elapsed(&self) -> usize {
+        self.elapsed
+    }
+
+    pub fn register(&self, register: Register) -> i64 {
+        self.registers[register.0 as usize]
+    }
+
+    pub fn x(&self) -> i64 {
+        self.register(Register::X)
+    }
+
+    /// The stack, with its top last
+    pub fn stack(&self) -> &[i64] {
+        &self.stack
+    }
+
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Executes one cycle, which completes the current instruction if it was its last one
+    pub fn tick(&mut self) -> Result<()> {
+        let instruction = match self.current() {
+            Some(instruction) => instruction,
+            None => return Ok(()),
+        };
+        self.cycle += 1;
+        self.elapsed += 1;
+        if self.elapsed == instruction.cycles() {
+            self.elapsed = 0;
+            self.execute(instruction)?;
+        }
+        Ok(())
+    }
+
+    /// Executes the remaining cycles of the current instruction and returns their number
+    pub fn step(&mut self) -> Result<usize> {
+        let start = self.cycle;
+        while !self.halted {
+            self.tick()?;
+            if self.elapsed == 0 {
+                break;
+            }
+        }
+        Ok(self.cycle - start)
+    }
+
+    /// Runs the program until it halts, calling `during` with the state of every cycle
+    /// before it executes. Fails after `CYCLE_LIMIT` cycles.
+    pub fn run<F>(&mut self, mut during: F) -> Result<()>
+    where
+        F: FnMut(&Cpu),
+    {
+        while !self.halted {
+            self.check_cycle_limit()?;
+            during(self);
+            self.tick()?;
+        }
+        Ok(())
+    }
+
+    /// Fails once the next cycle lies beyond `CYCLE_LIMIT`
+    pub fn check_cycle_limit(&self) -> Result<()> {
+        if self.cycle > CYCLE_LIMIT {
+            return Err(Error::invalid_state(format!(
+                "the program did not halt within {} cycles",
+                CYCLE_LIMIT
+            )));
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, instruction: Instruction) -> Result<()> {
+        let pc = self.pc;
+        let mut next = pc + 1;
+        let overflow = |register| {
+            Error::invalid_state(format!(
+                "'{}' at {} overflows {}",
+                instruction, pc, register
+            ))
+        };
+        match instruction {
+            Instruction::Noop => {}
+            Instruction::Addx(register, value) => {
+                self.registers[register.0 as usize] = self
+                    .register(register)
+                    .checked_add(value.into())
+                    .ok_or_else(|| overflow(register))?
+            }
+            Instruction::Mulx(register, value) => {
+                self.registers[register.0 as usize] = self
+                    .register(register)
+                    .checked_mul(value.into())
+                    .ok_or_else(|| overflow(register))?
+            }
+            Instruction::Jmp(offset) => next = self.jump(offset)?,
+            Instruction::Jnz(register, offset) => {
+                if self.register(register) != 0 {
+                    next = self.jump(offset)?;
+                }
+            }
+            Instruction::Push(register) => self.stack.push(self.register(register)),
+            Instruction::Pop(register) => {
+                self.registers[register.0 as usize] = self.stack.pop().ok_or_else(|| {
+                    Error::invalid_state(format!("'{}' at {} with an empty stack", instruction, pc))
+                })?
+            }
+            Instruction::Halt => {
+                self.halted = true;
+                next = pc;
+            }
+        }
+        self.pc = next;
+        self.halted |= self.pc == self.program.len();
+        Ok(())
+    }
+
+    /// Target of a jump, which may go to the end of the program to halt it
+    fn jump(&self, offset: i32) -> Result<usize> {
+        let target = self.pc as i64 + i64::from(offset);
+        if target < 0 || target > self.program.len() as i64 {
+            return Err(Error::invalid_state(format!(
+                "jump by {} at {} leaves the program",
+                offset, self.pc
+            )));
+        }
+        Ok(target as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycles() {
+        let mut cpu = Cpu::load(&Input::text("noop\naddx 3\naddx -5\n")).unwrap();
+        let mut xs = Vec::new();
+        cpu.run(|cpu| xs.push((cpu.cycle(), cpu.x()))).unwrap();
+        assert_eq!(vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)], xs);
+        assert_eq!((6, -1), (cpu.cycle(), cpu.x()));
+        assert!(cpu.is_halted());
+    }
+
+    #[test]
+    fn test_step_and_extended_instructions() {
+        // doubles x three times, counting down in a
+        let program = "addx 2\npush x\npop a\naddx -2\n\
+                       mulx 2\npush x\npush a\npop x\naddx -1\npush x\npop a\npop x\njnz a -8\n\
+                       halt\nnoop\n";
+        let mut cpu = Cpu::load(&Input::text(program)).unwrap();
+        assert_eq!(2, cpu.step().unwrap());
+        assert_eq!((3, 1), (cpu.x(), cpu.pc()));
+        cpu.tick().unwrap();
+        assert_eq!(vec![3], cpu.stack());
+        let a = Register::from_name("a").unwrap();
+        assert_eq!(Some(Instruction::Pop(a)), cpu.current());
+
+        cpu.run(|_| {}).unwrap();
+        assert_eq!((8, 0, 13), (cpu.x(), cpu.register(a), cpu.pc()));
+        assert_eq!(47, cpu.cycle());
+        assert_eq!(None, cpu.current());
+
+        let err = Cpu::load(&Input::text("pop a\n")).unwrap().step().unwrap_err();
+        assert_eq!(
+            "Invalid state: 'pop a' at 0 with an empty stack",
+            err.to_string()
+        );
+        let err = Cpu::new(vec![Instruction::Jmp(0)]).run(|_| {}).unwrap_err();
+        assert_eq!(
+            "Invalid state: the program did not halt within 1000000 cycles",
+            err.to_string()
+        );
+        assert!(Cpu::new(vec![Instruction::Jmp(-1)]).step().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic_on_registers() {
+        // a counts down from 3 while b triples, x only starts at 1 and stays there
+        let program = "addx a 3\naddx b 1\nmulx b 3\naddx a -1\njnz a -2\nmulx b -1\n";
+        let mut cpu = Cpu::load(&Input::text(program)).unwrap();
+        let (a, b) = (Register::from_name("a").unwrap(), Register::from_name("b").unwrap());
+        assert_eq!(Instruction::Mulx(b, 3), cpu.program()[2]);
+        assert_eq!("mulx b 3", cpu.program()[2].to_string());
+        assert_eq!("addx 4", Instruction::Addx(Register::X, 4).to_string());
+        cpu.run(|_| {}).unwrap();
+        assert_eq!((1, 0, -27), (cpu.x(), cpu.register(a), cpu.register(b)));
+
+        assert!(parse_instruction("addx xy 3").is_err());
+        let program = "addx c 2147483647\nmulx c 2147483647\nmulx c 2147483647\n";
+        let mut cpu = Cpu::load(&Input::text(program)).unwrap();
+        let err = cpu.run(|_| {}).unwrap_err();
+        assert_eq!(
+            "Invalid state: 'mulx c 2147483647' at 2 overflows c",
+            err.to_string()
+        );
+    }
+}
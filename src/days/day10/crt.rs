@@ -0,0 +1,343 @@
+use super::cpu::Cpu;
+use error::{Error, Result};
+use grid::{Grid, Point};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Size of the handheld's screen and of its sprite
+pub const WIDTH: usize = 40;
+pub const HEIGHT: usize = 6;
+pub const SPRITE_WIDTH: usize = 3;
+/// Most pixels a screen may have, scaled or not
+pub const MAX_PIXELS: usize = 1 << 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    pub width: usize,
+    pub height: usize,
+    pub sprite_width: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            width: WIDTH,
+            height: HEIGHT,
+            sprite_width: SPRITE_WIDTH,
+        }
+    }
+}
+
+/// How to render the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    /// `#` for lit pixels and `.` for dark ones
+    Ascii,
+    /// 🎁 for lit pixels and 🎄 for dark ones
+    Emoji,
+    /// blocks of two spaces on a red or green ANSI background
+    Ansi,
+    /// a plain black and white PBM image, with lit pixels in black
+    Pbm,
+    /// a plain PPM image in the colours of the emoji
+    Ppm,
+}
+
+impl Backend {
+    /// Whether the rendering is made for a terminal, and can be animated
+    pub fn is_text(self) -> bool {
+        matches!(self, Backend::Ascii | Backend::Emoji | Backend::Ansi)
+    }
+
+    /// Terminal columns taken by a pixel of a text rendering
+    pub fn cell_width(self) -> usize {
+        match self {
+            Backend::Emoji | Backend::Ansi => 2,
+            Backend::Ascii | Backend::Pbm | Backend::Ppm => 1,
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ascii" => Ok(Backend::Ascii),
+            "emoji" => Ok(Backend::Emoji),
+            "ansi" => Ok(Backend::Ansi),
+            "pbm" => Ok(Backend::Pbm),
+            "ppm" => Ok(Backend::Ppm),
+            _ => Err(Error::parse(format!(
+                "Expected 'ascii', 'emoji', 'ansi', 'pbm' or 'ppm', got '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// The handheld's screen. During every cycle, the beam draws one pixel, from left to
+/// right and top to bottom, which is lit if the sprite covers it.
+#[derive(Debug, Clone)]
+pub struct Crt {
+    pixels: Grid<bool>,
+    sprite_width: usize,
+    /// pixels drawn so far
+    drawn: usize,
+}
+
+impl Crt {
+    pub fn new(config: &Config) -> Result<Self> {
+        if config.width == 0 || config.height == 0 {
+            return Err(Error::invalid_state(format!(
+                "a screen of {}x{} pixels has no pixels",
+                config.width, config.height
+            )));
+        }
+        check_size(Some(config.width), Some(config.height))?;
+        Ok(Crt {
+            pixels: Grid::new(config.width, config.height, false)?,
+            sprite_width: config.sprite_width,
+            drawn: 0,
+        })
+    }
+
+    /// Lit pixels are `true`
+    pub fn pixels(&self) -> &Grid<bool> {
+        &self.pixels
+    }
+
+    /// The pixel the beam draws next, unless it finished the last row
+    pub fn beam(&self) -> Option<Point> {
+        let point = Point::new(
+            self.drawn % self.pixels.width(),
+            self.drawn / self.pixels.width(),
+        );
+        Some(point).filter(|point| point.y < self.pixels.height())
+    }
+
+    /// Columns covered by the sprite at position `x`, which is its middle column, or
+    /// the right one of the two middle columns of an even width. Columns beyond the range
+    /// of `i64` are cut off.
+    pub fn sprite(&self, x: i64) -> RangeInclusive<i64> {
+        let width = self.sprite_width as i64;
+        let left = x.saturating_sub(width / 2);
+        left..=left.saturating_add(width - 1)
+    }
+
+    /// Draws the pixel under the beam with the sprite at `x` and moves the beam on.
+    /// Once the beam finished the last row, nothing is drawn anymore.
+    pub fn draw(&mut self, x: i64) {
+        if let Some(beam) = self.beam() {
+            self.pixels[beam] = self.sprite(x).contains(&(beam.x as i64));
+            self.drawn += 1;
+        }
+    }
+
+    /// Runs the program to its end, drawing during every cycle
+    pub fn draw_program(&mut self, cpu: &mut Cpu) -> Result<()> {
+        cpu.run(|cpu| self.draw(cpu.x()))
+    }
+
+    /// Like `draw_program`, but calls `frame` with the screen and the sprite position
+    /// after every drawn pixel
+    pub fn animate<F>(&mut self, cpu: &mut Cpu, mut frame: F) -> Result<()>
+    where
+        F: FnMut(&Crt, i64),
+    {
+        cpu.run(|cpu| {
+            self.draw(cpu.x());
+            frame(self, cpu.x());
+        })
+    }
+
+    /// A finished copy with every pixel enlarged to `factor` x `factor` pixels, e.g. to
+    /// render a larger image. Fails if the copy would have more than `MAX_PIXELS` pixels.
+    pub fn scaled(&self, factor: usize) -> Result<Crt> {
+        if factor == 0 {
+            return Err(Error::invalid_state("a scale of 0 leaves no pixels"));
+        }
+        let (width, height) = (self.pixels.width(), self.pixels.height());
+        let len = check_size(width.checked_mul(factor), height.checked_mul(factor))?;
+        let mut pixels = Grid::new(width * factor, height * factor, false)?;
+        for (point, lit) in pixels.iter_mut() {
+            *lit = self.pixels[Point::new(point.x / factor, point.y / factor)];
+        }
+        Ok(Crt {
+            pixels,
+            sprite_width: self.sprite_width.saturating_mul(factor),
+            drawn: len,
+        })
+    }
+
+    pub fn render(&self, backend: Backend) -> String {
+        let rows = self.pixels.rows();
+        match backend {
+            Backend::Ascii => render_rows(rows, "#", ".", "", ""),
+            Backend::Emoji => render_rows(rows, "🎁", "🎄", "", ""),
+            Backend::Ansi => render_rows(rows, "\x1b[41m  ", "\x1b[42m  ", "", "\x1b[0m"),
+            Backend::Pbm => format!(
+                "P1\n{} {}\n{}",
+                self.pixels.width(),
+                self.pixels.height(),
+                render_rows(rows, "1", "0", " ", "")
+            ),
+            Backend::Ppm => format!(
+                "P3\n{} {}\n255\n{}",
+                self.pixels.width(),
+                self.pixels.height(),
+                render_rows(rows, "200 30 40", "20 120 40", " ", "")
+            ),
+        }
+    }
+
+    /// The screen as rendered by a text backend, followed by a line with the sprite at
+    /// `x` as `=` and a line marking the column of the last drawn pixel with `^`, both as
+    /// wide as the pixels of the backend
+    pub fn frame(&self, backend: Backend, x: i64) -> String {
+        let mut frame = self.render(backend);
+        let cell = backend.cell_width();
+        let sprite = self.sprite(x);
+        for col in 0..self.pixels.width() {
+            let mark = if sprite.contains(&(col as i64)) { "=" } else { " " };
+            frame.push_str(&mark.repeat(cell));
+        }
+        frame.push('\n');
+        if self.drawn > 0 {
+            let column = (self.drawn - 1) % self.pixels.width();
+            frame.push_str(&" ".repeat(column * cell));
+            frame.push_str(&"^".repeat(cell));
+            frame.push('\n');
+        }
+        frame
+    }
+}
+
+/// One line per row, with the pixels joined by `separator` and followed by `end`
+/// Number of pixels of a screen of `width` x `height` pixels, where `None` stands for a
+/// side that overflowed, unless it exceeds `MAX_PIXELS`
+fn check_size(width: Option<usize>, height: Option<usize>) -> Result<usize> {
+    match (width, height) {
+        (Some(width), Some(height)) => match width.checked_mul(height) {
+            Some(len) if len <= MAX_PIXELS => Ok(len),
+            _ => Err(Error::invalid_state(format!(
+                "a screen of {}x{} pixels has more than {} pixels",
+                width, height, MAX_PIXELS
+            ))),
+        },
+        _ => Err(Error::invalid_state(format!(
+            "a screen with more than {} pixels",
+            MAX_PIXELS
+        ))),
+    }
+}
+
+fn render_rows<'a, I>(rows: I, lit: &str, dark: &str, separator: &str, end: &str) -> String
+where
+    I: Iterator<Item = &'a [bool]>,
+{
+    let mut output = String::new();
+    for row in rows {
+        let pixels: Vec<&str> = row
+            .iter()
+            .map(|pixel| if *pixel { lit } else { dark })
+            .collect();
+        output.push_str(&pixels.join(separator));
+        output.push_str(end);
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use io::Input;
+
+    // x is 1 during cycles 1 to 3, 4 during cycles 4 and 5, and 2 from cycle 6 on
+    const PROGRAM: &str = "noop\naddx 3\naddx -2\nnoop\nnoop\nnoop\n";
+
+    fn drawn(width: usize, height: usize, sprite_width: usize) -> Crt {
+        let config = Config {
+            width,
+            height,
+            sprite_width,
+        };
+        let mut crt = Crt::new(&config).unwrap();
+        crt.draw_program(&mut Cpu::load(&Input::text(PROGRAM)).unwrap())
+            .unwrap();
+        crt
+    }
+
+    #[test]
+    fn test_render() {
+        let crt = drawn(4, 2, 3);
+        assert_eq!("####\n.###\n", crt.render(Backend::Ascii));
+        assert_eq!("🎁🎁🎁🎁\n🎄🎁🎁🎁\n", crt.render(Backend::Emoji));
+        assert_eq!("P1\n4 2\n1 1 1 1\n0 1 1 1\n", crt.render(Backend::Pbm));
+        assert!(crt
+            .render(Backend::Ppm)
+            .starts_with("P3\n4 2\n255\n200 30 40 200 30 40 200 30 40 200 30 40\n20 120 40 "));
+        assert!(crt
+            .render(Backend::Ansi)
+            .starts_with(&format!("{}\x1b[0m\n", "\x1b[41m  ".repeat(4))));
+        assert_eq!(
+            "########\n########\n..######\n..######\n",
+            crt.scaled(2).unwrap().render(Backend::Ascii)
+        );
+        assert!(crt.scaled(0).is_err());
+        assert!(crt.scaled(usize::MAX).is_err());
+        assert!(crt.scaled(MAX_PIXELS).is_err());
+
+        // a narrow sprite, and a screen that is not drawn to its end
+        assert_eq!(".#..\n..#.\n....\n", drawn(4, 3, 1).render(Backend::Ascii));
+        let config = Config {
+            width: 0,
+            ..Config::default()
+        };
+        assert!(Crt::new(&config).is_err());
+        let config = Config {
+            width: usize::MAX,
+            height: 2,
+            ..Config::default()
+        };
+        assert!(Crt::new(&config).is_err());
+    }
+
+    #[test]
+    fn test_animate() {
+        let config = Config {
+            width: 4,
+            height: 1,
+            sprite_width: 3,
+        };
+        let mut crt = Crt::new(&config).unwrap();
+        let mut cpu = Cpu::load(&Input::text("noop\naddx 2\n")).unwrap();
+        let mut frames = Vec::new();
+        crt.animate(&mut cpu, |crt, x| frames.push(crt.frame(Backend::Ascii, x)))
+            .unwrap();
+        assert_eq!(
+            vec!["#...\n=== \n^\n", "##..\n=== \n ^\n", "###.\n=== \n  ^\n"],
+            frames
+        );
+        assert_eq!(
+            "🎁🎁🎁🎄\n======  \n    ^^\n",
+            crt.frame(Backend::Emoji, 1)
+        );
+    }
+
+    #[test]
+    fn test_extreme_sprite_positions() {
+        let mut crt = Crt::new(&Config::default()).unwrap();
+        crt.draw(i64::MAX);
+        crt.draw(i64::MIN);
+        assert_eq!(i64::MAX - 1..=i64::MAX, crt.sprite(i64::MAX));
+        assert!(crt.pixels().rows().all(|row| row.iter().all(|lit| !lit)));
+        assert!(crt.frame(Backend::Ansi, i64::MAX).ends_with(&format!("{}\n  ^^\n", " ".repeat(80))));
+
+        let program = "mulx 153092023\nmulx 92737\nmulx 649657\n";
+        let mut cpu = Cpu::load(&Input::text(program)).unwrap();
+        crt.draw_program(&mut cpu).unwrap();
+        assert_eq!(i64::MAX, cpu.x());
+    }
+}
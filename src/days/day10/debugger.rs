@@ -0,0 +1,426 @@
+use super::cpu::{self, Cpu, Instruction, Register};
+use error::{Error, Result};
+use repl;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+step [N]            execute the rest of the current instruction, or N instructions
+tick [N]            execute one cycle, or N cycles
+continue            run until a breakpoint, a watched register changes or the program halts
+break [pc] N        stop before cycle N, or before the instruction at index N starts;
+                    without arguments, list the breakpoints
+clear               delete every breakpoint
+watch [REGISTER]    stop when the register changes; without arguments, list the watches
+unwatch REGISTER    stop watching the register
+print [REGISTER]    show the state of the CPU, or the value of a register
+trace [on|off]      log the cycle, pc, instruction and x of every executed cycle
+help                show this help
+exit                leave the debugger
+";
+
+/// The CPU during one cycle, as logged by `trace`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceEntry {
+    pub cycle: usize,
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub x: i64,
+}
+
+impl TraceEntry {
+    /// The state during the next cycle, unless the program halted
+    pub fn of(cpu: &Cpu) -> Option<Self> {
+        cpu.current().map(|instruction| TraceEntry {
+            cycle: cpu.cycle(),
+            pc: cpu.pc(),
+            instruction,
+            x: cpu.x(),
+        })
+    }
+}
+
+/// Formats the entry like `cycle 3  pc 1  addx 3  x=1`
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cycle {}  pc {}  {}  x={}",
+            self.cycle, self.pc, self.instruction, self.x
+        )
+    }
+}
+
+/// Runs the program to its end and returns the entry of every cycle
+pub fn trace(cpu: &mut Cpu) -> Result<Vec<TraceEntry>> {
+    let mut entries = Vec::new();
+    cpu.run(|cpu| entries.extend(TraceEntry::of(cpu)))?;
+    Ok(entries)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Breakpoint {
+    Cycle(usize),
+    Pc(usize),
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Cycle(cycle) => write!(f, "cycle {}", cycle),
+            Breakpoint::Pc(pc) => write!(f, "pc {}", pc),
+        }
+    }
+}
+
+/// A step debugger for the CPU, with breakpoints, watched registers and a trace log
+pub struct Debugger {
+    cpu: Cpu,
+    breakpoints: BTreeSet<Breakpoint>,
+    watches: Vec<Register>,
+    tracing: bool,
+}
+
+impl Debugger {
+    pub fn new(cpu: Cpu) -> Self {
+        Debugger {
+            cpu,
+            breakpoints: BTreeSet::new(),
+            watches: Vec::new(),
+            tracing: false,
+        }
+    }
+
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+
+    /// Executes one command line and returns its output
+    pub fn execute(&mut self, line: &str) -> Result<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Ok(String::new()),
+        };
+        match command {
+            "step" | "s" => {
+                let count = parse_count(command, args)?;
+                let mut output = String::new();
+                let start = self.cpu.cycle();
+                for _ in 0..count {
+                    while !self.cpu.is_halted() {
+                        self.check_cycle_limit(start)?;
+                        self.tick(&mut output)?;
+                        if self.cpu.elapsed() == 0 {
+                            break;
+                        }
+                    }
+                    if self.cpu.is_halted() {
+                        break;
+                    }
+                }
+                output.push_str(&self.state());
+                Ok(output)
+            }
+            "tick" => {
+                let count = parse_count(command, args)?;
+                let mut output = String::new();
+                let start = self.cpu.cycle();
+                for _ in 0..count {
+                    if self.cpu.is_halted() {
+                        break;
+                    }
+                    self.check_cycle_limit(start)?;
+                    self.tick(&mut output)?;
+                }
+                output.push_str(&self.state());
+                Ok(output)
+            }
+            "continue" | "c" | "clear" if !args.is_empty() => Err(Error::parse(format!(
+                "{}: takes no arguments",
+                command
+            ))),
+            "continue" | "c" => self.cont(),
+            "break" | "b" => self.set_breakpoint(args),
+            "clear" => {
+                self.breakpoints.clear();
+                Ok(String::new())
+            }
+            "watch" => match args {
+                [] => Ok(self
+                    .watches
+                    .iter()
+                    .map(|register| format!("{}={}\n", register, self.cpu.register(*register)))
+                    .collect()),
+                [name] => {
+                    let register = Register::from_name(name)?;
+                    if !self.watches.contains(&register) {
+                        self.watches.push(register);
+                    }
+                    Ok(String::new())
+                }
+                _ => Err(Error::parse("watch: too many arguments")),
+            },
+            "unwatch" => match args {
+                [name] => {
+                    let register = Register::from_name(name)?;
+                    self.watches.retain(|watched| *watched != register);
+                    Ok(String::new())
+                }
+                _ => Err(Error::parse("unwatch: expects one register")),
+            },
+            "print" | "p" => match args {
+                [] => Ok(self.state()),
+                [name] => {
+                    let register = Register::from_name(name)?;
+                    Ok(format!("{}={}\n", register, self.cpu.register(register)))
+                }
+                _ => Err(Error::parse("print: too many arguments")),
+            },
+            "trace" => {
+                self.tracing = match args {
+                    [] | ["on"] => true,
+                    ["off"] => false,
+                    _ => return Err(Error::parse("trace: expects 'on' or 'off'")),
+                };
+                Ok(String::new())
+            }
+            "help" => Ok(HELP.to_string()),
+            _ => Err(Error::parse(format!("Unknown command: {}", command))),
+        }
+    }
+
+    /// Executes commands line by line until `exit` or the end of the input.
+    /// Failing commands are reported on `err` and do not stop the debugger.
+    /// Returns the number of failed commands.
+    pub fn run<R, W, E>(&mut self, input: R, out: &mut W, err: &mut E, prompt: bool) -> io::Result<usize>
+    where
+        R: BufRead,
+        W: Write,
+        E: Write,
+    {
+        let prompt = Some(|debugger: &Debugger| format!("(cycle {}) ", debugger.cpu.cycle()))
+            .filter(|_| prompt);
+        repl::run(self, input, out, err, prompt, Debugger::execute)
+    }
+
+    /// Runs until a breakpoint is reached or a watched register changes, after at least
+    /// one cycle
+    fn cont(&mut self) -> Result<String> {
+        let mut output = String::new();
+        let start = self.cpu.cycle();
+        loop {
+            if self.cpu.is_halted() {
+                break;
+            }
+            self.check_cycle_limit(start)?;
+            let before: Vec<i64> = self
+                .watches
+                .iter()
+                .map(|register| self.cpu.register(*register))
+                .collect();
+            self.tick(&mut output)?;
+
+            let mut stopped = false;
+            for (register, old) in self.watches.iter().zip(before) {
+                let new = self.cpu.register(*register);
+                if new != old {
+                    output.push_str(&format!("watch {}: {} -> {}\n", register, old, new));
+                    stopped = true;
+                }
+            }
+            if let Some(breakpoint) = self.reached_breakpoint() {
+                output.push_str(&format!("breakpoint at {}\n", breakpoint));
+                stopped = true;
+            }
+            if stopped {
+                break;
+            }
+        }
+        output.push_str(&self.state());
+        Ok(output)
+    }
+
+    /// Fails once `CYCLE_LIMIT` cycles ran since cycle `start`, so that a single command
+    /// cannot run forever
+    fn check_cycle_limit(&self, start: usize) -> Result<()> {
+        if self.cpu.cycle() - start >= cpu::CYCLE_LIMIT {
+            return Err(Error::invalid_state(format!(
+                "no stop within {} cycles",
+                cpu::CYCLE_LIMIT
+            )));
+        }
+        Ok(())
+    }
+
+    /// Executes one cycle, logging it first when tracing
+    fn tick(&mut self, output: &mut String) -> Result<()> {
+        if self.tracing {
+            if let Some(entry) = TraceEntry::of(&self.cpu) {
+                output.push_str(&format!("{}\n", entry));
+            }
+        }
+        self.cpu.tick()
+    }
+
+    fn reached_breakpoint(&self) -> Option<Breakpoint> {
+        let at_cycle = Breakpoint::Cycle(self.cpu.cycle());
+        let at_pc = Breakpoint::Pc(self.cpu.pc());
+        if self.breakpoints.contains(&at_cycle) {
+            Some(at_cycle)
+        } else if self.cpu.elapsed() == 0 && self.breakpoints.contains(&at_pc) {
+            Some(at_pc)
+        } else {
+            None
+        }
+    }
+
+    fn set_breakpoint(&mut self, args: &[&str]) -> Result<String> {
+        let breakpoint = match args {
+            [] => {
+                return Ok(self
+                    .breakpoints
+                    .iter()
+                    .map(|breakpoint| format!("{}\n", breakpoint))
+                    .collect())
+            }
+            [cycle] => Breakpoint::Cycle(parse_number("break", cycle)?),
+            ["pc", pc] => Breakpoint::Pc(parse_number("break", pc)?),
+            _ => return Err(Error::parse("break: expects a cycle, or 'pc' and an index")),
+        };
+        self.breakpoints.insert(breakpoint);
+        Ok(String::new())
+    }
+
+    /// The next cycle, all registers other than 0 and the stack
+    fn state(&self) -> String {
+        let mut state = match TraceEntry::of(&self.cpu) {
+            Some(entry) => entry.to_string(),
+            None => format!(
+                "halted  cycle {}  pc {}  x={}",
+                self.cpu.cycle(),
+                self.cpu.pc(),
+                self.cpu.x()
+            ),
+        };
+        for register in Register::all() {
+            if register != Register::X && self.cpu.register(register) != 0 {
+                state.push_str(&format!("  {}={}", register, self.cpu.register(register)));
+            }
+        }
+        if !self.cpu.stack().is_empty() {
+            state.push_str(&format!("  stack={:?}", self.cpu.stack()));
+        }
+        state.push('\n');
+        state
+    }
+}
+
+fn parse_count(command: &str, args: &[&str]) -> Result<usize> {
+    match args {
+        [] => Ok(1),
+        [count] => parse_number(command, count),
+        _ => Err(Error::parse(format!("{}: too many arguments", command))),
+    }
+}
+
+fn parse_number(command: &str, arg: &str) -> Result<usize> {
+    arg.parse()
+        .map_err(|_| Error::parse(format!("{}: invalid number: {}", command, arg)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use io::Input;
+
+    const PROGRAM: &str = "noop\naddx 3\naddx -5\npush x\npop a\n";
+
+    fn debugger() -> Debugger {
+        Debugger::new(Cpu::load(&Input::text(PROGRAM)).unwrap())
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut debugger = debugger();
+        assert_eq!("cycle 2  pc 1  addx 3  x=1\n", debugger.execute("step").unwrap());
+        assert_eq!("cycle 3  pc 1  addx 3  x=1\n", debugger.execute("tick").unwrap());
+        debugger.execute("break pc 3").unwrap();
+        debugger.execute("watch a").unwrap();
+        assert_eq!(
+            "breakpoint at pc 3\ncycle 6  pc 3  push x  x=-1\n",
+            debugger.execute("continue").unwrap()
+        );
+        assert_eq!(
+            "watch a: 0 -> -1\nhalted  cycle 8  pc 5  x=-1  a=-1\n",
+            debugger.execute("c").unwrap()
+        );
+        assert_eq!("a=-1\n", debugger.execute("print a").unwrap());
+        assert!(debugger.execute("break pc").is_err());
+        assert!(debugger.execute("watch ab").is_err());
+        for (line, expected) in [
+            ("continue 3", "continue: takes no arguments"),
+            ("c 3", "c: takes no arguments"),
+            ("clear x", "clear: takes no arguments"),
+        ]
+        .iter()
+        {
+            assert_eq!(*expected, debugger.execute(line).unwrap_err().to_string());
+        }
+
+        // huge counts stop once halted, or at the cycle limit of an endless loop
+        let max = format!("{}", u64::MAX);
+        assert!(debugger.execute(&format!("tick {}", max)).unwrap().starts_with("halted"));
+        assert!(debugger.execute(&format!("step {}", max)).unwrap().starts_with("halted"));
+        let mut looping = Debugger::new(Cpu::load(&Input::text("noop\njmp -1\n")).unwrap());
+        for command in ["tick", "step"].iter() {
+            let err = looping.execute(&format!("{} {}", command, max)).unwrap_err();
+            assert_eq!(
+                format!("Invalid state: no stop within {} cycles", cpu::CYCLE_LIMIT),
+                err.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut debugger = debugger();
+        debugger.execute("trace on").unwrap();
+        debugger.execute("break 4").unwrap();
+        assert_eq!(
+            "cycle 1  pc 0  noop  x=1\n\
+             cycle 2  pc 1  addx 3  x=1\n\
+             cycle 3  pc 1  addx 3  x=1\n\
+             breakpoint at cycle 4\n\
+             cycle 4  pc 2  addx -5  x=4\n",
+            debugger.execute("continue").unwrap()
+        );
+
+        let mut cpu = Cpu::load(&Input::text(PROGRAM)).unwrap();
+        let entries = trace(&mut cpu).unwrap();
+        assert_eq!(7, entries.len());
+        assert_eq!(
+            "cycle 6  pc 3  push x  x=-1",
+            entries[5].to_string()
+        );
+    }
+
+    #[test]
+    fn test_run_script() {
+        let script = "break 3\ncontinue\nfrobnicate\nprint x\nexit\nprint\n";
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let failed = debugger()
+            .run(script.as_bytes(), &mut out, &mut err, false)
+            .unwrap();
+        assert_eq!(1, failed);
+        assert_eq!(
+            "breakpoint at cycle 3\ncycle 3  pc 1  addx 3  x=1\nx=1\n",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(
+            "Unknown command: frobnicate\n",
+            String::from_utf8(err).unwrap()
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use error::Result;
+use io::Input;
+use solver::{Answer, Part, Solver};
+
+pub mod cpu;
+pub mod crt;
+pub mod debugger;
+pub mod ocr;
+pub mod probe;
+
+pub use self::cpu::{parse_instruction, Cpu, Instruction, Register};
+pub use self::crt::Crt;
+use self::probe::{Probe, Schedule, Timing};
+
+pub struct Day10;
+
+impl Solver for Day10 {
+    fn day(&self) -> u32 {
+        10
+    }
+
+    fn input(&self) -> &'static str {
+        "program.txt"
+    }
+
+    fn part1(&self, input: &Input) -> Result<Answer> {
+        // during the 20th cycle and every 40 cycles after that
+        let probe = Probe::new(Schedule::every(20, 40, 6)?, Timing::During);
+        let samples = probe.run(&mut Cpu::load(input)?)?;
+        let sum_signal_strengths: i64 = samples.iter().map(|s| s.signal_strength).sum();
+        Ok(sum_signal_strengths.into())
+    }
+
+    fn part2(&self, input: &Input) -> Result<Answer> {
+        Ok(ocr::read(draw(input)?.pixels())?.into())
+    }
+
+    /// The image on the CRT
+    fn render(&self, input: &Input) -> Option<Result<String>> {
+        Some(draw(input).map(|crt| crt.render(crt::Backend::Emoji)))
+    }
+
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("Sum of signal strengths: {}", answer),
+            Part::Two => format!("Letters on the CRT: {}", answer),
+        }
+    }
+}
+
+/// Draws the image of the program on the handheld's CRT
+fn draw(input: &Input) -> Result<Crt> {
+    let mut crt = Crt::new(&crt::Config::default())?;
+    crt.draw_program(&mut Cpu::load(input)?)?;
+    Ok(crt)
+}
@@ -0,0 +1,150 @@
+use error::{Error, Result};
+use grid::{Grid, Point};
+
+/// Size of a letter in pixels
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+/// Columns taken by each letter on the screen, including the gap to the next one
+pub const CELL_WIDTH: usize = 5;
+
+/// The capital letters of the handheld's font, row by row with `#` for lit pixels
+const FONT: &[(char, &str)] = &[
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// Letters read from a screen, with `?` for the glyphs that are not in the font
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub text: String,
+    /// First column of every unknown glyph
+    pub unknown: Vec<usize>,
+}
+
+/// Reads a screen of letters side by side, each in a cell of `CELL_WIDTH` columns.
+/// Empty cells are spaces.
+pub fn scan(pixels: &Grid<bool>) -> Result<Reading> {
+    if pixels.height() != GLYPH_HEIGHT {
+        return Err(Error::invalid_state(format!(
+            "letters are {} pixels high, but the screen has {} rows",
+            GLYPH_HEIGHT,
+            pixels.height()
+        )));
+    }
+    let mut reading = Reading {
+        text: String::new(),
+        unknown: Vec::new(),
+    };
+    for left in (0..pixels.width()).step_by(CELL_WIDTH) {
+        let right = (left + CELL_WIDTH).min(pixels.width());
+        let letter = if (left..right).all(|x| pixels.column(x).all(|lit| !lit)) {
+            Some(' ')
+        } else {
+            glyph(pixels, left).and_then(|glyph| {
+                FONT.iter()
+                    .find(|(_, pattern)| *pattern == glyph)
+                    .map(|(letter, _)| *letter)
+            })
+        };
+        match letter {
+            Some(letter) => reading.text.push(letter),
+            None => {
+                reading.text.push('?');
+                reading.unknown.push(left);
+            }
+        }
+    }
+    Ok(reading)
+}
+
+/// Like `scan`, but fails on glyphs that are not in the font
+pub fn read(pixels: &Grid<bool>) -> Result<String> {
+    let reading = scan(pixels)?;
+    if !reading.unknown.is_empty() {
+        let columns: Vec<String> = reading.unknown.iter().map(|x| x.to_string()).collect();
+        return Err(Error::not_found(format!(
+            "no letter for the glyphs at columns {} of '{}'",
+            columns.join(", "),
+            reading.text
+        )));
+    }
+    Ok(reading.text)
+}
+
+/// The pixels of the glyph in the cell starting at column `left`, unless its gap is lit
+/// or it is cut off by the edge of the screen
+fn glyph(pixels: &Grid<bool>, left: usize) -> Option<String> {
+    if left + GLYPH_WIDTH > pixels.width() {
+        return None;
+    }
+    let gap = left + GLYPH_WIDTH;
+    if gap < pixels.width() && pixels.column(gap).any(|lit| *lit) {
+        return None;
+    }
+    let mut glyph = String::with_capacity(GLYPH_WIDTH * GLYPH_HEIGHT);
+    for y in 0..GLYPH_HEIGHT {
+        for x in left..gap {
+            glyph.push(if pixels[Point::new(x, y)] { '#' } else { '.' });
+        }
+    }
+    Some(glyph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(rows: &[&str]) -> Grid<bool> {
+        Grid::from_rows(
+            rows.iter()
+                .map(|row| row.chars().map(|c| c == '#').collect())
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_read() {
+        let pixels = screen(&[
+            "#..#..###........##.",
+            "#..#...#..........#.",
+            "####...#..........#.",
+            "#..#...#..........#.",
+            "#..#...#.......#..#.",
+            "#..#..###.......##..",
+        ]);
+        assert_eq!(
+            Reading {
+                text: "HI J".to_string(),
+                unknown: Vec::new()
+            },
+            scan(&pixels).unwrap()
+        );
+
+        let pixels = screen(&[
+            "####.#...#", "#....#...#", "###..#...#", "#....#...#", "#....#...#", "#....#...#",
+        ]);
+        let reading = scan(&pixels).unwrap();
+        assert_eq!(("F?".to_string(), vec![5]), (reading.text, reading.unknown));
+        assert_eq!(
+            "Not found: no letter for the glyphs at columns 5 of 'F?'",
+            read(&pixels).unwrap_err().to_string()
+        );
+        assert!(scan(&screen(&["#"])).is_err());
+    }
+}
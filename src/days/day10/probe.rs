@@ -0,0 +1,199 @@
+use super::cpu::{Cpu, Register};
+use error::{Error, Result};
+use std::collections::BTreeSet;
+
+/// When a probe reads the register of a cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timing {
+    /// While the cycle executes, before the effect of an instruction ending in it
+    During,
+    /// Once the cycle completed, including the effect of an instruction ending in it
+    After,
+}
+
+/// The cycles to probe, counted from 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule(BTreeSet<usize>);
+
+impl Schedule {
+    pub fn cycles<I>(cycles: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let cycles: BTreeSet<usize> = cycles.into_iter().collect();
+        if cycles.contains(&0) {
+            return Err(Error::invalid_state("cycles are counted from 1"));
+        }
+        Ok(Schedule(cycles))
+    }
+
+    /// `count` cycles from `start` on, `step` cycles apart
+    pub fn every(start: usize, step: usize, count: usize) -> Result<Self> {
+        let mut cycles = Vec::with_capacity(count);
+        for i in 0..count {
+            let cycle = i
+                .checked_mul(step)
+                .and_then(|offset| offset.checked_add(start))
+                .ok_or_else(|| {
+                    Error::invalid_state(format!(
+                        "{} cycles from {} on, {} apart, go beyond the last cycle",
+                        count, start, step
+                    ))
+                })?;
+            cycles.push(cycle);
+        }
+        Schedule::cycles(cycles)
+    }
+}
+
+/// A register read by a probe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub cycle: usize,
+    pub value: i64,
+    /// The cycle number times the value
+    pub signal_strength: i64,
+}
+
+/// Reads a register, `x` unless chosen otherwise, at the cycles of a schedule
+#[derive(Debug, Clone)]
+pub struct Probe {
+    schedule: Schedule,
+    timing: Timing,
+    register: Register,
+}
+
+impl Probe {
+    pub fn new(schedule: Schedule, timing: Timing) -> Self {
+        Probe {
+            schedule,
+            timing,
+            register: Register::X,
+        }
+    }
+
+    pub fn register(mut self, register: Register) -> Self {
+        self.register = register;
+        self
+    }
+
+    /// Runs the program up to the last scheduled cycle and returns a sample for every
+    /// scheduled cycle it reached. The last cycle of a program can be probed after it
+    /// completed, but not any cycle later. Fails after `CYCLE_LIMIT` cycles.
+    pub fn run(&self, cpu: &mut Cpu) -> Result<Vec<Sample>> {
+        let mut samples = Vec::new();
+        let last = match self.schedule.0.iter().next_back() {
+            Some(last) => *last,
+            None => return Ok(samples),
+        };
+        while !cpu.is_halted() && cpu.cycle() <= last {
+            cpu.check_cycle_limit()?;
+            let cycle = cpu.cycle();
+            if self.timing == Timing::During {
+                self.sample(cpu, cycle, &mut samples)?;
+            }
+            cpu.tick()?;
+            if self.timing == Timing::After {
+                self.sample(cpu, cycle, &mut samples)?;
+            }
+        }
+        Ok(samples)
+    }
+
+    fn sample(&self, cpu: &Cpu, cycle: usize, samples: &mut Vec<Sample>) -> Result<()> {
+        if self.schedule.0.contains(&cycle) {
+            let value = cpu.register(self.register);
+            // cycles stay within `CYCLE_LIMIT`
+            let signal_strength = (cycle as i64).checked_mul(value).ok_or_else(|| {
+                    Error::invalid_state(format!(
+                        "the signal strength of {}={} during cycle {} overflows",
+                        self.register, value, cycle
+                    ))
+                })?;
+            samples.push(Sample {
+                cycle,
+                value,
+                signal_strength,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::cpu::Instruction;
+    use super::*;
+    use io::Input;
+
+    fn probe(schedule: Schedule, timing: Timing) -> Vec<(usize, i64)> {
+        let mut cpu = Cpu::load(&Input::text("noop\naddx 3\naddx -5\n")).unwrap();
+        Probe::new(schedule, timing)
+            .run(&mut cpu)
+            .unwrap()
+            .iter()
+            .map(|sample| (sample.cycle, sample.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_timing() {
+        // addx 3 takes cycles 2 and 3, x only changes once the second one completed
+        let all = Schedule::every(1, 1, 10).unwrap();
+        assert_eq!(
+            vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)],
+            probe(all.clone(), Timing::During)
+        );
+        assert_eq!(
+            vec![(1, 1), (2, 1), (3, 4), (4, 4), (5, -1)],
+            probe(all, Timing::After)
+        );
+        assert_eq!(
+            vec![(3, 1), (4, 4)],
+            probe(Schedule::cycles(vec![4, 3]).unwrap(), Timing::During)
+        );
+        assert!(Schedule::cycles(vec![0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_signal_strength() {
+        let mut cpu = Cpu::load(&Input::text("addx 5\naddx -1\nmulx 3\n")).unwrap();
+        let a = Register::from_name("a").unwrap();
+        let samples = Probe::new(Schedule::every(2, 2, 3).unwrap(), Timing::During)
+            .run(&mut cpu.clone())
+            .unwrap();
+        let strengths: Vec<i64> = samples.iter().map(|s| s.signal_strength).collect();
+        assert_eq!(vec![2, 24, 30], strengths);
+
+        let samples = Probe::new(Schedule::every(2, 2, 3).unwrap(), Timing::During)
+            .register(a)
+            .run(&mut cpu)
+            .unwrap();
+        assert!(samples.iter().all(|sample| sample.value == 0));
+        // the probe stops after the last scheduled cycle
+        assert_eq!(7, cpu.cycle());
+    }
+
+    #[test]
+    fn test_limits() {
+        assert!(Schedule::every(2, usize::MAX / 2, 3).is_err());
+
+        let mut cpu = Cpu::load(&Input::text("mulx 153092023\nmulx 92737\nmulx 649657\n")).unwrap();
+        let err = Probe::new(Schedule::every(1, 1, 20).unwrap(), Timing::After)
+            .run(&mut cpu)
+            .unwrap_err();
+        assert_eq!(
+            "Invalid state: the signal strength of x=9223372036854775807 during cycle 9 overflows",
+            err.to_string()
+        );
+
+        let mut cpu = Cpu::new(vec![Instruction::Jmp(0)]);
+        let err = Probe::new(Schedule::cycles(vec![2_000_000]).unwrap(), Timing::During)
+            .run(&mut cpu)
+            .unwrap_err();
+        assert_eq!(
+            "Invalid state: the program did not halt within 1000000 cycles",
+            err.to_string()
+        );
+    }
+}
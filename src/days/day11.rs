@@ -1,17 +1,31 @@
-use io;
+use error::{Error, Result};
+use io::{self, Input};
+use solver::{Answer, Part, Solver};
 
 type Uint = u64;
 
-pub fn day11(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day11, which is a valid file path.");
+pub struct Day11;
+
+impl Solver for Day11 {
+    fn day(&self) -> u32 {
+        11
+    }
+
+    fn input(&self) -> &'static str {
+        "monkeys.txt"
     }
 
-    let mut highscore = play_monkey_keepaway(&args[0], 20, true);
-    print_monkeybusiness(&highscore);
+    fn part1(&self, input: &Input) -> Result<Answer> {
+        Ok(monkeybusiness(&play_monkey_keepaway(input, 20, true)?).into())
+    }
+
+    fn part2(&self, input: &Input) -> Result<Answer> {
+        Ok(monkeybusiness(&play_monkey_keepaway(input, 10000, false)?).into())
+    }
 
-    highscore = play_monkey_keepaway(&args[0], 10000, false);
-    print_monkeybusiness(&highscore);
+    fn describe(&self, _part: Part, answer: &Answer) -> String {
+        format!("level of monkey business: {}", answer)
+    }
 }
 
 fn gcd(a: Uint, b: Uint) -> Uint {
@@ -31,17 +45,16 @@ struct Score {
     id: usize,
 }
 
-fn print_monkeybusiness(highscore: &Vec<Score>) {
+fn monkeybusiness(highscore: &[Score]) -> Uint {
     let mut monkeybusiness: Uint = 1;
     for score in highscore[0..2].iter() {
         monkeybusiness *= score.score;
     }
-    println!("level of monkey business: {}", monkeybusiness);
+    monkeybusiness
 }
 
-fn play_monkey_keepaway(filename: &str, rounds: usize, worry_div_3: bool) -> Vec<Score> {
-    println!("Playing {} rounds of keep away", rounds);
-    let mut monkeys = parse_monkeys(filename);
+fn play_monkey_keepaway(input: &Input, rounds: usize, worry_div_3: bool) -> Result<Vec<Score>> {
+    let mut monkeys = parse_monkeys(input)?;
 
     let mut div: Uint;
     let manage_worry: Box<dyn Fn(Uint) -> Uint> = match worry_div_3 {
@@ -71,31 +84,60 @@ fn play_monkey_keepaway(filename: &str, rounds: usize, worry_div_3: bool) -> Vec
             id: i,
             score: m.inspect_count,
         };
-        println!("Monkey {} inspected items {} times.", i, score.score);
         highscore.push(score);
     }
     // sort descending
-    highscore.sort_by(|a, b| b.cmp(&a));
-    highscore
+    highscore.sort_by(|a, b| b.cmp(a));
+    Ok(highscore)
 }
 
-fn parse_monkeys(filename: &str) -> Vec<Monkey> {
+fn parse_monkeys(input: &Input) -> Result<Vec<Monkey>> {
     let mut monkeys: Vec<Monkey> = Vec::new();
-    io::foreach_nonempty_line(filename, |line| {
+    io::foreach_nonempty_line(input, |line| {
         let parts = line.split_whitespace().collect::<Vec<&str>>();
-        if !(parts.len() >= 2) {
-            panic!("Invalid line {}", line);
+        if parts.len() < 2 {
+            return Err(Error::parse(format!("Invalid line {}", line)));
+        }
+        if parts[0] == "Monkey" {
+            monkeys.push(Monkey::new());
+            return Ok(());
         }
+        let monkey = monkeys
+            .last_mut()
+            .ok_or_else(|| Error::parse("Monkey attributes before first 'Monkey' line"))?;
         match parts[0] {
-            "Monkey" => monkeys.push(Monkey::new()),
-            "Starting" => monkeys.last_mut().unwrap().parse_items(&parts[2..]),
-            "Operation:" => monkeys.last_mut().unwrap().parse_operation(&parts[3..]),
-            "Test:" => monkeys.last_mut().unwrap().divisor = parts[3].parse::<Uint>().unwrap(),
-            "If" => monkeys.last_mut().unwrap().parse_target(parts[1], parts[5]),
-            _ => panic! {"Unrecognized start of string: {}", parts[0]},
+            "Starting" => monkey.parse_items(&parts[2..]),
+            "Operation:" if parts.len() > 3 => monkey.parse_operation(&parts[3..]),
+            "Test:" if parts.len() > 3 => {
+                monkey.divisor = parts[3].parse::<Uint>()?;
+                if monkey.divisor == 0 {
+                    return Err(Error::parse("Items cannot be tested for divisibility by 0"));
+                }
+                Ok(())
+            }
+            "If" if parts.len() > 5 => monkey.parse_target(parts[1], parts[5]),
+            _ => Err(Error::parse(format!("Invalid line {}", line))),
+        }
+    })?;
+    if monkeys.len() < 2 {
+        return Err(Error::parse("Need at least two monkeys"));
+    }
+    for (id, monkey) in monkeys.iter().enumerate() {
+        if monkey.divisor == 0 {
+            return Err(Error::parse(format!("Monkey {} has no test", id)));
         }
-    });
-    monkeys
+        for target in [monkey.true_targ, monkey.false_targ].iter() {
+            if *target == id || *target >= monkeys.len() {
+                return Err(Error::parse(format!(
+                    "Monkey {} throws to monkey {}, which must be another one of the {}",
+                    id,
+                    target,
+                    monkeys.len()
+                )));
+            }
+        }
+    }
+    Ok(monkeys)
 }
 
 #[derive(Clone)]
@@ -125,15 +167,19 @@ impl Monkey {
             inspect_count: 0,
         }
     }
-    fn parse_items(&mut self, items: &[&str]) {
+    fn parse_items(&mut self, items: &[&str]) -> Result<()> {
         for i in items {
-            let item = i.replace(',', "").parse::<Uint>().unwrap();
+            let item = i.replace(',', "").parse::<Uint>()?;
             self.items.push(item);
         }
+        Ok(())
     }
-    fn parse_operation(&mut self, parts: &[&str]) {
+    fn parse_operation(&mut self, parts: &[&str]) -> Result<()> {
         if parts.len() != 3 {
-            panic!("Operation must consist of 3 parts: {:#?}", parts);
+            return Err(Error::parse(format!(
+                "Operation must consist of 3 parts: {:?}",
+                parts
+            )));
         }
         self.op = match parts[1] {
             "+" => match parts[2].parse::<Uint>() {
@@ -144,15 +190,17 @@ impl Monkey {
                 Ok(n) => Operation::Mul(Some(n)),
                 Err(_) => Operation::Mul(None),
             },
-            _ => panic!("Invalid operation: {}", parts[1]),
-        }
+            _ => return Err(Error::parse(format!("Invalid operation: {}", parts[1]))),
+        };
+        Ok(())
     }
-    fn parse_target(&mut self, cond: &str, target: &str) {
+    fn parse_target(&mut self, cond: &str, target: &str) -> Result<()> {
         match cond {
-            "true:" => self.true_targ = target.parse::<usize>().unwrap(),
-            "false:" => self.false_targ = target.parse::<usize>().unwrap(),
-            _ => panic!("Invalid conditional: {}", cond),
+            "true:" => self.true_targ = target.parse::<usize>()?,
+            "false:" => self.false_targ = target.parse::<usize>()?,
+            _ => return Err(Error::parse(format!("Invalid conditional: {}", cond))),
         }
+        Ok(())
     }
     fn operation(&self, old: Uint) -> Uint {
         match &self.op {
@@ -174,7 +222,7 @@ impl Monkey {
         self.inspect_count += 1;
         item = self.operation(item);
         item = manage_worry(item);
-        let target_monkey = if item % Uint::from(self.divisor) == 0 {
+        let target_monkey = if item % self.divisor == 0 {
             self.true_targ
         } else {
             self.false_targ
@@ -182,3 +230,46 @@ impl Monkey {
         (item, target_monkey)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monkeys(divisor: &str, target: &str) -> String {
+        format!(
+            "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = old * 19\n\
+             Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey 1\n\n\
+             Monkey 1:\n  Starting items: 54\n  Operation: new = old + 6\n  Test: divisible by 19\n\
+             If true: throw to monkey 0\n    If false: throw to monkey 0\n",
+            divisor, target
+        )
+    }
+
+    #[test]
+    fn test_invalid_monkeys() {
+        assert!(Day11.part2(&Input::text(&monkeys("23", "1"))).is_ok());
+        assert_eq!(
+            "<text>:4: Items cannot be tested for divisibility by 0",
+            Day11
+                .part2(&Input::text(&monkeys("0", "1")))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Monkey 0 throws to monkey 2, which must be another one of the 2",
+            Day11
+                .part1(&Input::text(&monkeys("23", "2")))
+                .unwrap_err()
+                .to_string()
+        );
+        assert!(Day11.part1(&Input::text(&monkeys("23", "0"))).is_err());
+        let untested = monkeys("23", "1").replace("Test: divisible by 23\n", "");
+        assert_eq!(
+            "Monkey 0 has no test",
+            Day11
+                .part1(&Input::text(&untested))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
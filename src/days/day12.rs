@@ -1,219 +1,165 @@
-use direction::Direction;
-use io;
+use error::{Error, Result};
+use grid::{Grid, Point};
+use io::Input;
+use solver::{Answer, Part, Solver};
 use std::cmp;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::fmt;
 
-pub fn day12(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day12, which is a valid file path.");
-    }
-
-    let mut map = parse_map(&args[0]);
+pub struct Day12;
 
-    assert!(!map.is_empty());
-    assert_eq!(map.y_end, map.map.len());
-    assert_eq!(map.x_end, map.map[0].len());
-    // dbg!(map.y_end);
-    // dbg!(map.x_end);
+impl Solver for Day12 {
+    fn day(&self) -> u32 {
+        12
+    }
 
-    // print!("{}", map);
+    fn input(&self) -> &'static str {
+        "heightmap.txt"
+    }
 
-    let length = dijkstra(&mut map).unwrap();
+    fn part1(&self, input: &Input) -> Result<Answer> {
+        let mut map = load_map(input)?;
+        let length =
+            dijkstra(&mut map).ok_or_else(|| Error::not_found("path from S to E"))?;
+        Ok(length.into())
+    }
 
-    println!("Shortest path length from S to E: {}", length);
+    fn part2(&self, input: &Input) -> Result<Answer> {
+        let mut map = load_map(input)?;
 
-    let start_height = to_height('a');
-    let mut start_coords: Vec<Coord> = Vec::new();
-    for (y, row) in map.map.iter_mut().enumerate() {
-        for (x, grid) in row.iter_mut().enumerate() {
-            if grid.height == start_height {
-                start_coords.push(Coord { x, y, distance: 0 });
+        let start_height = to_height('a')?;
+        let start_coords: Vec<Coord> = map
+            .map
+            .iter()
+            .filter(|(_, square)| square.height == start_height)
+            .map(|(pos, _)| Coord { pos, distance: 0 })
+            .collect();
+        let mut min_length = usize::MAX;
+        for start in start_coords {
+            map.my_pos = start.clone();
+            if let Some(length) = dijkstra(&mut map) {
+                min_length = cmp::min(min_length, length);
             }
         }
-    }
-    let mut min_length = usize::MAX;
-    for start in start_coords {
-        map.my_pos = start.clone();
-        if let Some(length) = dijkstra(&mut map) {
-            min_length = cmp::min(min_length, length);
+        if min_length == usize::MAX {
+            return Err(Error::not_found("path from any 'a' to E"));
         }
+        Ok(min_length.into())
     }
-    println!("Shortest path length from S to E: {}", min_length);
-}
 
-fn parse_map(filename: &str) -> HeightMap {
-    let mut map: HeightMap = HeightMap::new();
-    let mut y: usize = 0;
-    let mut x_len: Option<usize> = None;
-    io::foreach_nonempty_line(filename, |line| {
-        if x_len.is_none() {
-            x_len = Some(line.len())
-        } else if let Some(l) = x_len {
-            assert_eq!(l, line.len());
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("Shortest path length from S to E: {}", answer),
+            Part::Two => format!("Shortest path length from any 'a' to E: {}", answer),
         }
-        let mut row = vec![Grid::new(); line.len()];
-        for (x, c) in line.chars().enumerate() {
-            row[x].height = to_height(c);
+    }
+}
 
-            if c == 'S' {
-                map.my_pos = Coord::new(x, y);
-                map.my_pos.distance = 0;
-                row[x].distance = 0;
-            }
-            if c == 'E' {
-                map.target_pos = Coord::new(x, y);
-            }
+fn load_map(input: &Input) -> Result<HeightMap> {
+    let mut my_pos: Option<Point> = None;
+    let mut target_pos: Option<Point> = None;
+    let map = Grid::parse(input, |pos, c| {
+        match c {
+            'S' => my_pos = Some(pos),
+            'E' => target_pos = Some(pos),
+            _ => (),
         }
-        map.map.push(row);
-        y += 1;
-    });
-    map.x_end = x_len.unwrap();
-    map.y_end = y;
-    map
+        Ok(Square::new(to_height(c)?))
+    })?;
+
+    // the whole map was read without finding them, so report the last row
+    let last_row = map.height();
+    let my_pos = my_pos.ok_or_else(|| Error::parse("No start 'S'").at(&input.name(), last_row))?;
+    let target_pos =
+        target_pos.ok_or_else(|| Error::parse("No target 'E'").at(&input.name(), last_row))?;
+    // print!("{}", map);
+    Ok(HeightMap {
+        map,
+        my_pos: Coord::new(my_pos),
+        target_pos: Coord::new(target_pos),
+    })
 }
 
-fn to_height(c: char) -> u8 {
+fn to_height(c: char) -> Result<u8> {
     match c {
         'S' => to_height('a'),
         'E' => to_height('z'),
-        _ => c.to_digit(36).unwrap() as u8,
+        'a'..='z' => Ok(c.to_digit(36).unwrap() as u8),
+        _ => Err(Error::parse(format!("Invalid height: {:?}", c))),
     }
 }
 
-#[derive(Default)]
 struct HeightMap {
-    pub map: Vec<Vec<Grid>>,
-    pub x_end: usize,
-    pub y_end: usize,
+    pub map: Grid<Square>,
     pub my_pos: Coord,
     pub target_pos: Coord,
 }
 
 impl HeightMap {
-    fn new() -> Self {
-        Default::default()
-    }
-    fn is_empty(&self) -> bool {
-        self.map.is_empty()
-    }
-    fn at(&self, coord: &Coord) -> &Grid {
-        assert!(self.is_inside(coord.x as i32, coord.y as i32));
-        &self.map[coord.y][coord.x]
-    }
-    fn at_mut(&mut self, coord: &Coord) -> &mut Grid {
-        assert!(self.is_inside(coord.x as i32, coord.y as i32));
-        &mut self.map[coord.y][coord.x]
+    fn at(&self, coord: &Coord) -> &Square {
+        &self.map[coord.pos]
     }
-    fn is_inside(&self, x: i32, y: i32) -> bool {
-        (0..self.y_end as i32).contains(&y) && (0..self.x_end as i32).contains(&x)
-    }
-    /// Get the neighbour, if any, in the desired direction
-    fn neighbour(&self, coord: &Coord, dir: &Direction) -> Option<Coord> {
-        // NOTE: in this case, up and down are reversed, so up would be y - 1
-        match dir {
-            Direction::Up => {
-                if (coord.y as i32 - 1) >= 0 {
-                    Some(Coord::new(coord.x, coord.y - 1))
-                } else {
-                    None
-                }
-            }
-            Direction::Down => {
-                if (coord.y as i32 + 1) < self.y_end as i32 {
-                    Some(Coord::new(coord.x, coord.y + 1))
-                } else {
-                    None
-                }
-            }
-            Direction::Left => {
-                if (coord.x as i32 - 1) >= 0 {
-                    Some(Coord::new(coord.x - 1, coord.y))
-                } else {
-                    None
-                }
-            }
-            Direction::Right => {
-                if (coord.x as i32 + 1) < self.x_end as i32 {
-                    Some(Coord::new(coord.x + 1, coord.y))
-                } else {
-                    None
-                }
-            }
-        }
+    fn at_mut(&mut self, coord: &Coord) -> &mut Square {
+        &mut self.map[coord.pos]
     }
     /// Get a vector of coordinates that are possible next moves in the map
     fn get_neighbours_if<F>(&self, cur: &Coord, mut predicate: F) -> Vec<Coord>
     where
         F: FnMut(&Coord) -> bool,
     {
-        let mut neighbours: Vec<Coord> = Vec::new();
-        for dir in Direction::iter() {
-            if let Some(next) = self.neighbour(&cur, dir) {
-                if predicate(&next) {
-                    neighbours.push(next);
-                }
-            }
-        }
-        neighbours
-    }
-    #[allow(dead_code)]
-    fn foreach_coord<F>(&mut self, coordinates: &Vec<Coord>, mut f: F)
-    where
-        F: FnMut(&mut Grid),
-    {
-        for c in coordinates {
-            f(&mut self.at_mut(&c));
-        }
+        self.map
+            .neighbours(cur.pos)
+            .map(Coord::new)
+            .filter(|next| predicate(next))
+            .collect()
     }
 }
 
 impl fmt::Display for HeightMap {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (y, row) in self.map.iter().enumerate() {
-            for (x, grid) in row.iter().enumerate() {
-                let cur_pos = Coord::new(x, y);
-                if cur_pos == self.my_pos {
-                    write!(f, "[🧝]")?;
-                } else if cur_pos == self.target_pos {
-                    write!(f, "[🚩]")?;
-                } else {
-                    write!(f, "[{:#02}]", grid.height)?;
-                }
+        for (pos, square) in self.map.iter() {
+            if pos == self.my_pos.pos {
+                write!(f, "[🧝]")?;
+            } else if pos == self.target_pos.pos {
+                write!(f, "[🚩]")?;
+            } else {
+                write!(f, "[{:#02}]", square.height)?;
+            }
+            if pos.x + 1 == self.map.width() {
+                writeln!(f)?;
             }
-            write!(f, "\n")?;
         }
         Ok(())
     }
 }
 
 #[derive(Default, Clone)]
-struct Grid {
+struct Square {
     height: u8,
     visited: bool,
     distance: usize,
 }
-impl Grid {
-    fn new() -> Self {
-        let mut res: Self = Default::default();
-        res.distance = usize::MAX;
-        res
+impl Square {
+    fn new(height: u8) -> Self {
+        Square {
+            height,
+            distance: usize::MAX,
+            ..Default::default()
+        }
     }
 }
 
 #[derive(Default, Clone, Debug)]
 struct Coord {
-    x: usize,
-    y: usize,
+    pos: Point,
     distance: usize,
 }
 
 impl Coord {
-    fn new(x: usize, y: usize) -> Self {
+    fn new(pos: Point) -> Self {
         Coord {
-            x,
-            y,
+            pos,
             distance: usize::MAX,
         }
     }
@@ -221,7 +167,7 @@ impl Coord {
 
 impl PartialEq for Coord {
     fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
+        self.pos == other.pos
     }
 }
 impl Eq for Coord {}
@@ -231,8 +177,8 @@ impl Ord for Coord {
         other
             .distance
             .cmp(&self.distance)
-            .then_with(|| self.x.cmp(&other.x))
-            .then_with(|| self.y.cmp(&other.y))
+            .then_with(|| self.pos.x.cmp(&other.pos.x))
+            .then_with(|| self.pos.y.cmp(&other.pos.y))
     }
 }
 
@@ -244,11 +190,9 @@ impl PartialOrd for Coord {
 
 fn dijkstra(map: &mut HeightMap) -> Option<usize> {
     // make sure to reset visited and distance
-    for row in map.map.iter_mut() {
-        for grid in row.iter_mut() {
-            grid.visited = false;
-            grid.distance = usize::MAX;
-        }
+    for square in map.map.values_mut() {
+        square.visited = false;
+        square.distance = usize::MAX;
     }
     map.my_pos.distance = 0;
     map.at_mut(&map.my_pos.clone()).distance = 0;
@@ -268,9 +212,9 @@ fn dijkstra(map: &mut HeightMap) -> Option<usize> {
         let mut neighbours = map.get_neighbours_if(&cur, |neighbour| {
             // a valid neighbour has have a distance greater than one farther than the current
             // (otherwise that grid already has a shorther path to it)
-            map.at(&neighbour).distance > cur_distance + 1
+            map.at(neighbour).distance > cur_distance + 1
             // and has to not have been visited yet
-                && !map.at(&neighbour).visited
+                && !map.at(neighbour).visited
             // and it has to be no higher than one step
                 && ((map.at(neighbour).height == map.at(&cur).height + 1)
                 // or any lower distance
@@ -280,7 +224,7 @@ fn dijkstra(map: &mut HeightMap) -> Option<usize> {
         // set the new, lower distances of the neighboring grids
         for c in neighbours.iter_mut() {
             c.distance = cur_distance + 1;
-            map.at_mut(&c).distance = c.distance;
+            map.at_mut(c).distance = c.distance;
         }
 
         // mark current as visited
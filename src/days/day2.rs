@@ -1,26 +1,50 @@
-use io;
+use error;
+use io::{self, Input};
+use solver::{Answer, Part, Solver};
 use std::error::Error;
 use std::fmt;
-use std::path::Path;
 
-pub fn day2(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day2, which is a valid file path.");
+pub struct Day2;
+
+impl Solver for Day2 {
+    fn day(&self) -> u32 {
+        2
     }
 
-    let incorrect_score = run_strategy(&args[0], |a, b| {
-        rock_paper_scissors(Hand::from_str(a).unwrap(), Hand::from_str(b).unwrap())
-    });
+    fn input(&self) -> &'static str {
+        "strategy_guide.txt"
+    }
 
-    println!("Following the strategy guide INCORRECTLY we would get a score of {incorrect_score}");
+    fn part1(&self, input: &Input) -> error::Result<Answer> {
+        let score = run_strategy(input, |a, b| {
+            let a = Hand::from_str(a)?;
+            let b = Hand::from_str(b)?;
+            Ok(rock_paper_scissors(a, b))
+        })?;
+        Ok(score.into())
+    }
 
-    let correct_score = run_strategy(&args[0], |a, b| {
-        let a = Hand::from_str(a).unwrap();
-        let b = Strategy::from_str(b).unwrap();
-        rock_paper_scissors(a, strategy_to_hand(a, b))
-    });
-    println!("Following the strategy guide CORRECTLY we would get a score of {correct_score}");
+    fn part2(&self, input: &Input) -> error::Result<Answer> {
+        let score = run_strategy(input, |a, b| {
+            let a = Hand::from_str(a)?;
+            let b = Strategy::from_str(b)?;
+            Ok(rock_paper_scissors(a, strategy_to_hand(a, b)))
+        })?;
+        Ok(score.into())
+    }
+
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!(
+                "Following the strategy guide INCORRECTLY we would get a score of {answer}"
+            ),
+            Part::Two => format!(
+                "Following the strategy guide CORRECTLY we would get a score of {answer}"
+            ),
+        }
+    }
 }
+
 #[derive(Debug, Clone, Copy)]
 enum Hand {
     Rock = 0,
@@ -37,6 +61,11 @@ impl fmt::Display for HandInvalidErr {
         write!(f, "Could not convert to Hand : {}", self.msg)
     }
 }
+impl From<HandInvalidErr> for error::Error {
+    fn from(err: HandInvalidErr) -> Self {
+        error::Error::parse(err.to_string())
+    }
+}
 impl Hand {
     fn as_str(&self) -> &'static str {
         match self {
@@ -62,23 +91,19 @@ impl fmt::Display for Hand {
     }
 }
 
-fn run_strategy<P, F>(filename: P, score_func: F) -> u32
+fn run_strategy<F>(input: &Input, score_func: F) -> error::Result<u32>
 where
-    P: AsRef<Path>,
-    F: Fn(&str, &str) -> u32,
+    F: Fn(&str, &str) -> error::Result<u32>,
 {
     let mut score: u32 = 0;
 
-    if let Ok(lines) = io::read_lines(filename) {
-        for line in lines {
-            if let Ok(line) = line {
-                if let Some((op_hand, strat)) = line.split_once(' ') {
-                    score += score_func(op_hand, strat);
-                }
-            }
+    io::foreach_line(input, |line| {
+        if let Some((op_hand, strat)) = line.split_once(' ') {
+            score += score_func(op_hand, strat)?;
         }
-    }
-    return score;
+        Ok(())
+    })?;
+    Ok(score)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -97,6 +122,11 @@ impl fmt::Display for StrategyInvalidErr {
         write!(f, "Could not convert to Strategy: {}", self.msg)
     }
 }
+impl From<StrategyInvalidErr> for error::Error {
+    fn from(err: StrategyInvalidErr) -> Self {
+        error::Error::parse(err.to_string())
+    }
+}
 impl Strategy {
     fn as_str(&self) -> &'static str {
         match self {
@@ -131,7 +161,7 @@ fn strategy_to_hand(opponent_hand: Hand, strategy: Strategy) -> Hand {
             Hand::Scissors => Hand::Paper,
         },
         // Y means we need to draw
-        Strategy::Draw => opponent_hand.clone(),
+        Strategy::Draw => opponent_hand,
         // Z means we need to win
         Strategy::Win => match opponent_hand {
             Hand::Rock => Hand::Paper,
@@ -144,7 +174,7 @@ fn strategy_to_hand(opponent_hand: Hand, strategy: Strategy) -> Hand {
 fn rock_paper_scissors(opponent_hand: Hand, player_hand: Hand) -> u32 {
     let op_idx = hand_index(&opponent_hand);
     let pl_idx = hand_index(&player_hand);
-    return SCORE_MATRIX[pl_idx][op_idx];
+    SCORE_MATRIX[pl_idx][op_idx]
 }
 
 const SCORE_MATRIX: [[u32; 3]; 3] = [[4, 1, 7], [8, 5, 2], [3, 9, 6]];
@@ -1,23 +1,38 @@
-use io;
+use error::{Error, Result};
+use io::{self, Input};
+use solver::{Answer, Part, Solver};
 use std::collections::HashSet;
 
-pub fn day3(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day3, which is a valid file path.");
+pub struct Day3;
+
+impl Solver for Day3 {
+    fn day(&self) -> u32 {
+        3
     }
 
-    let total_cost = sum_backpack_rearrangement_costs(&args[0]);
+    fn input(&self) -> &'static str {
+        "rucksacks.txt"
+    }
 
-    println!("The total cost to rearrange is {total_cost}");
+    fn part1(&self, input: &Input) -> Result<Answer> {
+        Ok(sum_backpack_rearrangement_costs(input)?.into())
+    }
 
-    let badge_total = sum_group_badge_costs(&args[0]);
+    fn part2(&self, input: &Input) -> Result<Answer> {
+        Ok(sum_group_badge_costs(input)?.into())
+    }
 
-    println!("The sum of badge values is {badge_total}");
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("The total cost to rearrange is {answer}"),
+            Part::Two => format!("The sum of badge values is {answer}"),
+        }
+    }
 }
 
-fn sum_group_badge_costs(filename: &str) -> u32 {
+fn sum_group_badge_costs(input: &Input) -> Result<u32> {
     let mut costs: u32 = 0;
-    io::foreach_linegroup(filename, 3, |group| {
+    io::foreach_linegroup(input, 3, |group| {
         // transform all rucksacks of the group into sets
         let mut group_sets: Vec<HashSet<char>> = Vec::new();
         for s in group {
@@ -26,49 +41,52 @@ fn sum_group_badge_costs(filename: &str) -> u32 {
 
         // fold all sets into one result set
         let all_inter_set = group_sets.iter().fold(group_sets[0].clone(), |lhs, rhs| {
-            lhs.intersection(&rhs).cloned().collect()
+            lhs.intersection(rhs).cloned().collect()
         });
 
-        if let Some(common_char) = all_inter_set.iter().nth(0) {
-            costs += to_priority(common_char.clone())
-                .unwrap_or_else(|| panic!("Not a valid character: {}", common_char));
+        if let Some(common_char) = all_inter_set.iter().next() {
+            costs += priority_of(*common_char)?;
         } else {
-            panic!("Group does not have any overlapping items: {:?}", group);
+            return Err(Error::invalid_state(format!(
+                "Group does not have any overlapping items: {:?}",
+                group
+            )));
         }
-    });
-    costs
+        Ok(())
+    })?;
+    Ok(costs)
 }
 
-fn sum_backpack_rearrangement_costs(filename: &str) -> u32 {
+fn sum_backpack_rearrangement_costs(input: &Input) -> Result<u32> {
     let mut sum: u32 = 0;
-    io::foreach_line(filename, |line| {
+    io::foreach_line(input, |line| {
         let mid = line.len() / 2;
         let (first_comp, second_comp) = line.split_at(mid);
         // find common character
-        let common_char = find_first_common(first_comp, second_comp).unwrap_or_else(|| {
-            panic!(
+        let common_char = find_first_common(first_comp, second_comp).ok_or_else(|| {
+            Error::invalid_state(format!(
                 "Compartments did not have overlapping items! {} and {}",
                 first_comp, second_comp
-            )
-        });
+            ))
+        })?;
 
         // convert character to priority and add to sum
-        let p = to_priority(common_char)
-            .unwrap_or_else(|| panic!("Not a valid character: {}", common_char));
-        sum += p;
-    });
-    sum
+        sum += priority_of(common_char)?;
+        Ok(())
+    })?;
+    Ok(sum)
+}
+
+fn priority_of(c: char) -> Result<u32> {
+    to_priority(c).ok_or_else(|| Error::parse(format!("Not a valid character: {}", c)))
 }
 
 fn to_priority(c: char) -> Option<u32> {
     let upper_offset = c.is_uppercase() as u32 * 26;
-    if c.is_digit(10) {
+    if c.is_ascii_digit() {
         None
     } else {
-        match c.to_digit(36) {
-            Some(d) => Some(d - 9 + upper_offset),
-            None => None,
-        }
+        c.to_digit(36).map(|d| d - 9 + upper_offset)
     }
 }
 
@@ -84,10 +102,7 @@ fn find_first_common(lhs: &str, rhs: &str) -> Option<char> {
     let lhs_set = to_set(lhs);
     let rhs_set = to_set(rhs);
 
-    match lhs_set.intersection(&rhs_set).nth(0) {
-        Some(c) => Some(c.clone()),
-        None => None,
-    }
+    lhs_set.intersection(&rhs_set).next().copied()
 }
 
 #[cfg(test)]
@@ -136,4 +151,15 @@ mod tests {
 
         assert_eq!(None, find_first_common("ABC", "DEF"));
     }
+
+    #[test]
+    fn test_no_common_item_is_invalid_state() {
+        let input = Input::text("vJrwpWtwJgWr\nabcdef\n");
+        let err = Day3.part1(&input).unwrap_err();
+        assert!(matches!(err, Error::InvalidState { .. }));
+        assert_eq!(
+            "<text>:2: Invalid state: Compartments did not have overlapping items! abc and def",
+            err.to_string()
+        );
+    }
 }
@@ -1,57 +1,71 @@
-use io;
+use error;
+use io::{self, Input};
+use solver::{Answer, Part, Solver};
 use std::error::Error;
 use std::fmt;
 
-pub fn day4(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day4, which is a valid file path.");
+pub struct Day4;
+
+impl Solver for Day4 {
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn input(&self) -> &'static str {
+        "assignments.txt"
     }
 
-    println!(
-        "Number of full range overlaps: {}",
-        number_full_overlaps(&args[0])
-    );
+    fn part1(&self, input: &Input) -> error::Result<Answer> {
+        Ok(number_full_overlaps(input)?.into())
+    }
+
+    fn part2(&self, input: &Input) -> error::Result<Answer> {
+        Ok(number_any_overlaps(input)?.into())
+    }
 
-    println!(
-        "Number of any range overlaps: {}",
-        number_any_overlaps(&args[0])
-    );
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("Number of full range overlaps: {}", answer),
+            Part::Two => format!("Number of any range overlaps: {}", answer),
+        }
+    }
 }
 
-fn number_full_overlaps(filename: &str) -> u32 {
+fn number_full_overlaps(input: &Input) -> error::Result<u32> {
     let mut count: u32 = 0;
-    foreach_rangepair(filename, |lhs, rhs| {
-        if lhs.contains(&rhs) || rhs.contains(&lhs) {
+    foreach_rangepair(input, |lhs, rhs| {
+        if lhs.contains(rhs) || rhs.contains(lhs) {
             count += 1;
         }
-    });
-    count
+    })?;
+    Ok(count)
 }
 
-fn number_any_overlaps(filename: &str) -> u32 {
+fn number_any_overlaps(input: &Input) -> error::Result<u32> {
     let mut count = 0;
-    foreach_rangepair(filename, |lhs, rhs| {
-        if lhs.overlaps(&rhs) {
+    foreach_rangepair(input, |lhs, rhs| {
+        if lhs.overlaps(rhs) {
             count += 1
         }
-    });
-    count
+    })?;
+    Ok(count)
 }
 
-fn foreach_rangepair<F>(filename: &str, mut f: F)
+fn foreach_rangepair<F>(input: &Input, mut f: F) -> error::Result<()>
 where
     F: FnMut(&Range, &Range),
 {
-    io::foreach_line(filename, |line| {
-        let (lhs, rhs) = line
-            .split_once(',')
-            .unwrap_or_else(|| panic!("Cannot split line on ',': {}", line));
+    io::foreach_line(input, |line| {
+        let (lhs, rhs) = line.split_once(',').ok_or_else(|| {
+            error::Error::parse(format!("Cannot split line on ',': {}", line))
+        })?;
 
-        let lhs_range = Range::from_str(lhs).unwrap();
-        let rhs_range = Range::from_str(rhs).unwrap();
+        let lhs_range = Range::from_str(lhs)?;
+        let rhs_range = Range::from_str(rhs)?;
 
         f(&lhs_range, &rhs_range);
-    });
+        Ok(())
+    })
 }
 
 struct Range {
@@ -93,7 +107,14 @@ enum RangeError {
 impl Error for RangeError {}
 impl fmt::Display for RangeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Could not create Range : {}", self)
+        match self {
+            RangeError::Parse(msg) => write!(f, "Could not create Range : {}", msg),
+        }
+    }
+}
+impl From<RangeError> for error::Error {
+    fn from(err: RangeError) -> Self {
+        error::Error::parse(err.to_string())
     }
 }
 impl std::convert::From<std::num::ParseIntError> for RangeError {
@@ -110,30 +131,30 @@ mod tests {
     fn test_range_contains() {
         let r1 = Range { start: 0, end: 5 };
         let r2 = Range { start: 1, end: 4 };
-        assert_eq!(true, r1.contains(&r2));
-        assert_eq!(false, r2.contains(&r1));
+        assert!(r1.contains(&r2));
+        assert!(!r2.contains(&r1));
         let r3 = Range { start: 0, end: 5 };
-        assert_eq!(true, r1.contains(&r3));
-        assert_eq!(true, r3.contains(&r1));
+        assert!(r1.contains(&r3));
+        assert!(r3.contains(&r1));
 
         let r4 = Range { start: 6, end: 6 };
         let r5 = Range { start: 4, end: 6 };
-        assert_eq!(true, r5.contains(&r4));
+        assert!(r5.contains(&r4));
     }
     #[test]
     fn test_range_overlaps() {
         let r1 = Range { start: 0, end: 5 };
         let r2 = Range { start: 1, end: 4 };
-        assert_eq!(true, r1.overlaps(&r2));
-        assert_eq!(true, r2.overlaps(&r1));
+        assert!(r1.overlaps(&r2));
+        assert!(r2.overlaps(&r1));
         let r3 = Range { start: 5, end: 10 };
-        assert_eq!(true, r1.overlaps(&r3));
-        assert_eq!(true, r3.overlaps(&r1));
+        assert!(r1.overlaps(&r3));
+        assert!(r3.overlaps(&r1));
 
         let r4 = Range { start: 0, end: 5 };
         let r5 = Range { start: 6, end: 6 };
-        assert_eq!(false, r5.overlaps(&r4));
-        assert_eq!(false, r4.overlaps(&r5));
+        assert!(!r5.overlaps(&r4));
+        assert!(!r4.overlaps(&r5));
     }
     #[test]
     fn test_from_str() {
@@ -1,25 +1,49 @@
-use io;
-use std::io::prelude::*;
+use error::{Error, Result};
+use io::{self, Input};
+use solver::{Answer, Part, Solver};
 
-pub fn day5(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day5, which is a valid file path.");
+pub struct Day5;
+
+impl Solver for Day5 {
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn input(&self) -> &'static str {
+        "crates.txt"
+    }
+
+    fn part1(&self, input: &Input) -> Result<Answer> {
+        let (mut stacks, move_ops) = parse_crates(input)?;
+        move_crates_onebyone(&mut stacks, &move_ops);
+        Ok(top_of_stack(&stacks).into())
+    }
+
+    fn part2(&self, input: &Input) -> Result<Answer> {
+        let (mut stacks, move_ops) = parse_crates(input)?;
+        move_crates_together(&mut stacks, &move_ops)?;
+        Ok(top_of_stack(&stacks).into())
     }
 
-    solve(&args[0]);
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("Top of the stacks after incorrect move: '{}'", answer),
+            Part::Two => format!("Top of the stacks after correct move: '{}'", answer),
+        }
+    }
 }
 
-fn parse_into_stacks(input: &mut Vec<String>) -> Vec<Vec<char>> {
+fn parse_into_stacks(input: &mut Vec<String>) -> Result<Vec<Vec<char>>> {
     let mut stacks: Vec<Vec<char>> = Vec::new();
 
-    let size = input
+    let labels = input
         .pop()
-        .unwrap()
-        .rsplit_once(' ')
-        .unwrap()
-        .1
-        .parse::<usize>()
-        .unwrap();
+        .ok_or_else(|| Error::parse("Missing stack drawing"))?;
+    let size = labels
+        .split_whitespace()
+        .last()
+        .ok_or_else(|| Error::parse(format!("Missing stack labels: '{}'", labels)))?
+        .parse::<usize>()?;
     stacks.resize(size, Vec::new());
 
     while !input.is_empty() {
@@ -27,86 +51,96 @@ fn parse_into_stacks(input: &mut Vec<String>) -> Vec<Vec<char>> {
         for (k, c) in line.chars().enumerate() {
             if k != 0 && ((k - 1) % 4 == 0) && c != ' ' {
                 let x = k / 4;
+                if x >= size {
+                    return Err(Error::parse(format!("Crate outside of stacks: '{}'", line)));
+                }
                 stacks[x].push(c);
             }
         }
     }
 
-    stacks
+    Ok(stacks)
 }
 
 struct MoveOp {
     n: usize,
     src: usize,
     dest: usize,
+    /// line of the operation in the input
+    line: usize,
 }
 
-fn solve(filename: &str) {
-    let mut reader = io::get_reader(filename).unwrap();
-    let mut line = String::new();
-
-    // first loop: accumulate the stackstate into a string-stack until we reach the first empty line
+fn parse_crates(input: &Input) -> Result<(Vec<Vec<char>>, Vec<MoveOp>)> {
+    // the stackstate is accumulated into a string-stack until we reach the first empty line,
+    // after which each line is a move operation
     let mut string_stack: Vec<String> = Vec::new();
-    loop {
-        match reader.read_line(&mut line) {
-            Ok(n) => {
-                if n == 0 {
-                    break;
-                }
-                if line == "\n" {
-                    break;
-                }
+    let mut drawing_done = false;
+    let mut move_ops: Vec<MoveOp> = Vec::new();
+    let mut number = 0;
+
+    io::foreach_line(input, |line| {
+        number += 1;
+        if !drawing_done {
+            if line.is_empty() {
+                drawing_done = true;
+            } else {
                 string_stack.push(line.trim_end().to_string());
-                line.clear();
             }
-            Err(e) => panic!("{}", e),
+        } else if !line.is_empty() {
+            move_ops.push(parse_move(line, number)?);
+        }
+        Ok(())
+    })?;
+
+    let stacks = parse_into_stacks(&mut string_stack)?;
+    for op in &move_ops {
+        if op.src >= stacks.len() || op.dest >= stacks.len() {
+            return Err(Error::parse(format!(
+                "Move between stacks {} and {}, but there are {} stacks",
+                op.src + 1,
+                op.dest + 1,
+                stacks.len()
+            ))
+            .at(&input.name(), op.line));
         }
     }
 
-    // dbg!(&string_stack);
-
-    let mut stacks = parse_into_stacks(&mut string_stack);
-
-    let mut stacks_copy = stacks.clone();
-
-    // dbg!(&stacks);
-
-    let mut move_ops: Vec<MoveOp> = Vec::new();
+    Ok((stacks, move_ops))
+}
 
-    while reader.read_line(&mut line).unwrap() > 0 {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        assert_eq!(6, parts.len());
-        let n = parts[1].parse::<usize>().unwrap();
-        let src = parts[3].parse::<usize>().unwrap() - 1;
-        let dest = parts[5].parse::<usize>().unwrap() - 1;
-        move_ops.push(MoveOp { n, src, dest });
-        line.clear();
+fn parse_move(line: &str, number: usize) -> Result<MoveOp> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() != 6 {
+        return Err(Error::parse(format!("Invalid move: '{}'", line)));
     }
-
-    move_crates_onebyone(&mut stacks, &move_ops);
-    println!(
-        "Top of the stacks after incorrect move: '{}'",
-        top_of_stack(&stacks)
-    );
-
-    move_crates_together(&mut stacks_copy, &move_ops);
-    println!(
-        "Top of the stacks after correct move: '{}'",
-        top_of_stack(&stacks_copy)
-    );
+    let n = parts[1].parse::<usize>()?;
+    let src = parts[3].parse::<usize>()?;
+    let dest = parts[5].parse::<usize>()?;
+    if src == 0 || dest == 0 {
+        return Err(Error::parse(format!(
+            "Stacks are numbered from 1: '{}'",
+            line
+        )));
+    }
+    Ok(MoveOp {
+        n,
+        src: src - 1,
+        dest: dest - 1,
+        line: number,
+    })
 }
 
-fn top_of_stack(stacks: &Vec<Vec<char>>) -> String {
+fn top_of_stack(stacks: &[Vec<char>]) -> String {
     let mut top_of_stack = String::new();
     for stack in stacks {
         if let Some(top) = stack.last() {
-            top_of_stack.push(top.clone());
+            top_of_stack.push(*top);
         }
     }
     top_of_stack
 }
 
-fn move_crates_onebyone(stacks: &mut Vec<Vec<char>>, ops: &Vec<MoveOp>) {
+fn move_crates_onebyone(stacks: &mut [Vec<char>], ops: &[MoveOp]) {
     for op in ops {
         for _ in 0..op.n {
             if let Some(c) = stacks[op.src].pop() {
@@ -116,11 +150,46 @@ fn move_crates_onebyone(stacks: &mut Vec<Vec<char>>, ops: &Vec<MoveOp>) {
     }
 }
 
-fn move_crates_together(stacks: &mut Vec<Vec<char>>, ops: &Vec<MoveOp>) {
+/// Moves the crates of every operation at once, which fails if a stack has fewer crates
+fn move_crates_together(stacks: &mut [Vec<char>], ops: &[MoveOp]) -> Result<()> {
     for op in ops {
-        let n = stacks[op.src].len() - op.n;
+        let n = stacks[op.src].len().checked_sub(op.n).ok_or_else(|| {
+            Error::invalid_state(format!(
+                "Cannot move {} crates off stack {} with {}",
+                op.n,
+                op.src + 1,
+                stacks[op.src].len()
+            ))
+        })?;
         // stacks[op.dest].extend(stacks[op.src].drain(n..));
         let mut tmp: Vec<char> = stacks[op.src].drain(n..).collect();
         stacks[op.dest].append(&mut tmp);
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STACKS: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n";
+
+    #[test]
+    fn test_invalid_moves() {
+        let input = Input::text(&format!(
+            "{}move 1 from 2 to 1\nmove 1 from 4 to 1\n",
+            STACKS
+        ));
+        assert_eq!(
+            "<text>:7: Move between stacks 4 and 1, but there are 3 stacks",
+            Day5.part1(&input).unwrap_err().to_string()
+        );
+        // moving crates one by one stops at an empty stack
+        let input = Input::text(&format!("{}move 4 from 2 to 1\n", STACKS));
+        assert_eq!(Answer::from("MP"), Day5.part1(&input).unwrap());
+        assert_eq!(
+            "Invalid state: Cannot move 4 crates off stack 2 with 3",
+            Day5.part2(&input).unwrap_err().to_string()
+        );
+    }
 }
@@ -1,27 +1,38 @@
 use algorithm as algo;
-use io;
+use error;
+use io::Input;
 use iter::window_iter::*;
-use std::error;
+use solver::{Answer, Part, Solver};
 use std::fmt;
+use std::io::BufRead;
 
-pub fn day6(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day6, which is a valid file path.");
+pub struct Day6;
+
+impl Solver for Day6 {
+    fn day(&self) -> u32 {
+        6
     }
 
-    let (pos, marker) = find_first_unique::<4>(&args[0]).unwrap();
+    fn input(&self) -> &'static str {
+        "commstream.txt"
+    }
 
-    println!(
-        "Start-of-packet marker '{marker}' at position {pos}, meaning packet starts at character number {}",
-        pos + marker.len()
-    );
+    fn part1(&self, input: &Input) -> error::Result<Answer> {
+        let (pos, marker) = find_first_unique::<_, 4>(&mut input.reader()?)?;
+        Ok((pos + marker.len()).into())
+    }
 
-    let (pos, marker) = find_first_unique::<14>(&args[0]).unwrap();
+    fn part2(&self, input: &Input) -> error::Result<Answer> {
+        let (pos, marker) = find_first_unique::<_, 14>(&mut input.reader()?)?;
+        Ok((pos + marker.len()).into())
+    }
 
-    println!(
-        "Start-of-packet message '{marker}' at position {pos}, meaning message starts at character number {}",
-        pos + marker.len()
-    );
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("Start-of-packet marker ends at character number {answer}"),
+            Part::Two => format!("Start-of-message marker ends at character number {answer}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,17 +42,21 @@ impl fmt::Display for NoStart {
         write!(f, "Could not find start marker!")
     }
 }
-impl error::Error for NoStart {}
+impl std::error::Error for NoStart {}
+impl From<NoStart> for error::Error {
+    fn from(_: NoStart) -> Self {
+        error::Error::not_found("start marker")
+    }
+}
 
 /*
-   Opens a stream of elven communication and seek to the start-of-packet marker
+   Seeks a stream of elven communication to the start-of-packet marker
 */
-fn find_first_unique<const N: usize>(
-    filename: &str,
-) -> Result<(usize, String), Box<dyn std::error::Error>> {
-    let mut reader = io::get_reader(filename)?;
-
-    let iter = make_window_iter::<N>(&mut reader)?;
+fn find_first_unique<R, const N: usize>(reader: &mut R) -> Result<(usize, String), NoStart>
+where
+    R: BufRead,
+{
+    let iter = make_window_iter::<_, N>(reader).map_err(|_| NoStart)?;
 
     for (pos, bytes) in iter.enumerate() {
         let s: String = bytes.map(|b| b as char).iter().collect();
@@ -50,5 +65,21 @@ fn find_first_unique<const N: usize>(
             return Ok((pos, s));
         }
     }
-    Err(NoStart.into())
+    Err(NoStart)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        let input = Input::text("mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+        assert_eq!(Answer::UInt(7), Day6.part1(&input).unwrap());
+        assert_eq!(Answer::UInt(19), Day6.part2(&input).unwrap());
+
+        let input = Input::text("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg");
+        assert_eq!(Answer::UInt(10), Day6.part1(&input).unwrap());
+        assert_eq!(Answer::UInt(29), Day6.part2(&input).unwrap());
+    }
 }
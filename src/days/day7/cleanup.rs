@@ -0,0 +1,397 @@
+use super::FsTree;
+use error::{Error, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use tree::NodeId;
+
+/// Default size of the disk in day7
+pub const DISK_SIZE: usize = 70000000;
+/// Default free space needed by the update in day7
+pub const REQUIRED_FREE: usize = 30000000;
+
+/// What makes one set of directories to delete better than another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Objective {
+    /// the smallest total size
+    SmallestTotal,
+    /// the fewest directories, then the smallest total size
+    FewestDirs,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub disk_size: usize,
+    pub required_free: usize,
+    pub objective: Objective,
+    /// Paths that must survive the cleanup, together with everything below them
+    pub protected: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            disk_size: DISK_SIZE,
+            required_free: REQUIRED_FREE,
+            objective: Objective::SmallestTotal,
+            protected: Vec::new(),
+        }
+    }
+}
+
+/// Directories to delete, none of them inside another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    /// Path and size of every directory, in preorder
+    pub dirs: Vec<(String, usize)>,
+    pub freed: usize,
+    pub needed: usize,
+}
+
+/// Prints the plan as a shell script
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "# frees {} of the {} bytes needed",
+            self.freed, self.needed
+        )?;
+        for (path, _) in &self.dirs {
+            writeln!(f, "rm -rf {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bytes that still have to be freed for `config.required_free`, 0 if enough are free
+pub fn needed(fs: &FsTree, config: &Config) -> Result<usize> {
+    let used = fs[fs.root()].value.size();
+    let free = config.disk_size.checked_sub(used).ok_or_else(|| {
+        Error::invalid_state(format!(
+            "{} bytes are used on a disk of {} bytes",
+            used, config.disk_size
+        ))
+    })?;
+    Ok(config.required_free.saturating_sub(free))
+}
+
+/// Finds the best set of non-nested directories whose deletion leaves at least
+/// `config.required_free` bytes free.
+///
+/// Directories are decided on in preorder: deleting one skips its subtree, keeping it
+/// moves on to its first child. The search only visits totals that some set of directories
+/// actually frees, so its work depends on the directories and not on their sizes, and
+/// gives up on sets that can no longer free enough or beat the best plan found so far.
+pub fn plan(fs: &FsTree, config: &Config) -> Result<Plan> {
+    let needed = needed(fs, config)?;
+    if needed == 0 {
+        return Ok(Plan {
+            dirs: Vec::new(),
+            freed: 0,
+            needed,
+        });
+    }
+
+    let mut protected = Vec::new();
+    for path in &config.protected {
+        protected.push(fs.resolve(fs.root(), path)?);
+    }
+    let dirs = Dirs::new(fs, &protected);
+
+    // the smallest single directory that frees enough
+    let single = (0..dirs.ids.len())
+        .filter(|i| dirs.deletable[*i] && dirs.sizes[*i] >= needed)
+        .min_by_key(|i| dirs.sizes[*i]);
+    if let (Objective::FewestDirs, Some(i)) = (config.objective, single) {
+        return Ok(Plan {
+            dirs: vec![(fs.path(dirs.ids[i])?, dirs.sizes[i])],
+            freed: dirs.sizes[i],
+            needed,
+        });
+    }
+
+    let (freed, mut chosen) = dirs.search(needed, config.objective).ok_or_else(|| {
+        Error::not_found(format!(
+            "no set of deletable directories frees {} bytes",
+            needed
+        ))
+    })?;
+    chosen.sort();
+    Ok(Plan {
+        dirs: chosen
+            .into_iter()
+            .map(|i| Ok((fs.path(dirs.ids[i])?, dirs.sizes[i])))
+            .collect::<Result<_>>()?,
+        freed,
+        needed,
+    })
+}
+
+/// Positions of deleted directories, as a list shared between the sets that extend it
+struct Deleted {
+    dir: usize,
+    rest: Option<Rc<Deleted>>,
+}
+
+/// A set of deleted directories that does not free enough yet, with the position of the
+/// next directory to decide on
+struct Partial {
+    position: usize,
+    total: usize,
+    count: usize,
+    deleted: Option<Rc<Deleted>>,
+}
+
+/// The directories in preorder
+struct Dirs {
+    ids: Vec<NodeId>,
+    sizes: Vec<usize>,
+    /// position after the subtree of every directory
+    ends: Vec<usize>,
+    /// whether a directory neither is nor contains nor lies within a protected path
+    deletable: Vec<bool>,
+}
+
+impl Dirs {
+    fn new(fs: &FsTree, protected: &[NodeId]) -> Self {
+        let ids: Vec<NodeId> = fs
+            .preorder(fs.root())
+            .map(|visit| visit.id)
+            .filter(|id| fs[*id].value.is_dir())
+            .collect();
+        let ends = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                i + 1
+                    + ids[i + 1..]
+                        .iter()
+                        .take_while(|other| fs.is_descendant(**other, *id))
+                        .count()
+            })
+            .collect();
+        let deletable = ids
+            .iter()
+            .map(|id| {
+                !protected.iter().any(|protected| {
+                    fs.is_descendant(*protected, *id) || fs.is_descendant(*id, *protected)
+                })
+            })
+            .collect();
+        Dirs {
+            sizes: ids.iter().map(|id| fs[*id].value.size()).collect(),
+            ids,
+            ends,
+            deletable,
+        }
+    }
+
+    /// The most that can be freed with the directories from every position on
+    fn most_from(&self) -> Vec<usize> {
+        let mut most = vec![0; self.ids.len() + 1];
+        for position in (0..self.ids.len()).rev() {
+            most[position] = most[position + 1];
+            if self.deletable[position] {
+                most[position] =
+                    most[position].max(self.sizes[position] + most[self.ends[position]]);
+            }
+        }
+        most
+    }
+
+    /// The total and the positions of the best set of directories that frees `needed`.
+    /// Deleting is tried before keeping, so that good sets are found early and bound the
+    /// search, and every position and total is only searched with the fewest directories.
+    fn search(&self, needed: usize, objective: Objective) -> Option<(usize, Vec<usize>)> {
+        let key = |count: usize, total: usize| match objective {
+            Objective::SmallestTotal => (0, total),
+            Objective::FewestDirs => (count, total),
+        };
+        let most = self.most_from();
+        let mut best: Option<(usize, usize, Rc<Deleted>)> = None;
+        let mut searched: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut stack = vec![Partial {
+            position: 0,
+            total: 0,
+            count: 0,
+            deleted: None,
+        }];
+
+        while let Some(partial) = stack.pop() {
+            let Partial {
+                position,
+                total,
+                count,
+                ..
+            } = partial;
+            if position == self.ids.len() || total + most[position] < needed {
+                continue;
+            }
+            // even one more directory that frees exactly what is missing would not be better
+            let hopeless = best.as_ref().is_some_and(|(best_count, freed, _)| {
+                key(count + 1, needed) >= key(*best_count, *freed)
+            });
+            if hopeless || searched.get(&(position, total)).is_some_and(|c| *c <= count) {
+                continue;
+            }
+            searched.insert((position, total), count);
+
+            let rest = partial.deleted.clone();
+            stack.push(Partial {
+                position: position + 1,
+                ..partial
+            });
+            if !self.deletable[position] {
+                continue;
+            }
+            let deleted = Rc::new(Deleted {
+                dir: position,
+                rest,
+            });
+            let (count, total) = (count + 1, total + self.sizes[position]);
+            if total < needed {
+                stack.push(Partial {
+                    position: self.ends[position],
+                    total,
+                    count,
+                    deleted: Some(deleted),
+                });
+            } else if best.as_ref().is_none_or(|(best_count, freed, _)| {
+                key(count, total) < key(*best_count, *freed)
+            }) {
+                best = Some((count, total, deleted));
+            }
+        }
+
+        best.map(|(_, total, deleted)| {
+            let mut chosen = Vec::new();
+            let mut next = Some(&deleted);
+            while let Some(deleted) = next {
+                chosen.push(deleted.dir);
+                next = deleted.rest.as_ref();
+            }
+            (total, chosen)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use days::day7::load_filesystem;
+    use io::Input;
+
+    const LOG: &str = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n$ ls\n\
+                       dir e\n29116 f\n2557 g\n62596 h.lst\n$ cd e\n$ ls\n584 i\n$ cd ..\n$ cd ..\n\
+                       $ cd d\n$ ls\n4060174 j\n8033020 d.log\n5626152 d.ext\n7214296 k\n";
+
+    fn config(required_free: usize, objective: Objective, protected: &[&str]) -> Config {
+        Config {
+            disk_size: DISK_SIZE,
+            required_free,
+            objective,
+            protected: protected.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    /// Best (count, total) over all sets of non-nested deletable directories
+    fn brute_force(fs: &FsTree, config: &Config) -> Option<(usize, usize)> {
+        let needed = config.required_free - (config.disk_size - fs[fs.root()].value.size());
+        let protected: Vec<NodeId> = config
+            .protected
+            .iter()
+            .map(|p| fs.resolve(fs.root(), p).unwrap())
+            .collect();
+        let dirs = Dirs::new(fs, &protected);
+        let mut best: Option<(usize, usize)> = None;
+        for set in 0u32..1 << dirs.ids.len() {
+            let chosen: Vec<usize> = (0..dirs.ids.len()).filter(|i| set & 1 << i != 0).collect();
+            let nested = chosen.iter().any(|a| {
+                chosen
+                    .iter()
+                    .any(|b| a != b && fs.is_descendant(dirs.ids[*a], dirs.ids[*b]))
+            });
+            if nested || chosen.iter().any(|i| !dirs.deletable[*i]) {
+                continue;
+            }
+            let total: usize = chosen.iter().map(|i| dirs.sizes[*i]).sum();
+            let key = match config.objective {
+                Objective::SmallestTotal => (0, total),
+                Objective::FewestDirs => (chosen.len(), total),
+            };
+            if total >= needed && best.is_none_or(|best| key < best) {
+                best = Some(key);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_plan() {
+        let fs = load_filesystem(&Input::text(LOG)).unwrap();
+
+        let plan = plan(&fs, &Config::default()).unwrap();
+        assert_eq!(8381165, plan.needed);
+        assert_eq!(
+            "# frees 24933642 of the 8381165 bytes needed\nrm -rf /d\n",
+            plan.to_string()
+        );
+
+        let protect_k = config(REQUIRED_FREE, Objective::SmallestTotal, &["/d/k"]);
+        let err = super::plan(&fs, &protect_k).unwrap_err();
+        assert_eq!(
+            "Not found: no set of deletable directories frees 8381165 bytes",
+            err.to_string()
+        );
+        let protect_all = config(REQUIRED_FREE, Objective::FewestDirs, &["/"]);
+        assert!(super::plan(&fs, &protect_all).is_err());
+        assert_eq!(0, super::plan(&fs, &config(100, Objective::FewestDirs, &[])).unwrap().freed);
+    }
+
+    #[test]
+    fn test_plan_with_large_files() {
+        let fs = load_filesystem(&Input::text(
+            "$ cd /\n$ ls\ndir a\ndir b\ndir c\n$ cd a\n$ ls\n3000000000 a.log\n\
+             $ cd ../b\n$ ls\n4000000000 b.log\n$ cd ../c\n$ ls\n5000000000 c.log\n",
+        ))
+        .unwrap();
+        let config = Config {
+            disk_size: 20000000000,
+            required_free: 14000000000,
+            objective: Objective::SmallestTotal,
+            protected: vec!["/c".to_string()],
+        };
+        let plan = super::plan(&fs, &config).unwrap();
+        assert_eq!(
+            (vec![("/a".to_string(), 3000000000), ("/b".to_string(), 4000000000)], 7000000000),
+            (plan.dirs, plan.freed)
+        );
+    }
+
+    #[test]
+    fn test_plan_is_optimal() {
+        let fs = load_filesystem(&Input::text(
+            "$ cd /\n$ ls\ndir a\ndir b\n5 x\n$ cd a\n$ ls\ndir c\ndir d\n3 y\n$ cd c\n$ ls\n\
+             7 z\n$ cd ../d\n$ ls\n11 w\n$ cd /b\n$ ls\ndir e\n13 v\n$ cd e\n$ ls\n2 u\n",
+        ))
+        .unwrap();
+        let used = fs[fs.root()].value.size();
+        for objective in [Objective::SmallestTotal, Objective::FewestDirs].iter() {
+            for protected in [vec![], vec!["/a/c"], vec!["/b/e", "/a/d"]].iter() {
+                for needed in 1..=used {
+                    let config = config(DISK_SIZE - used + needed, *objective, protected);
+                    let expected = brute_force(&fs, &config);
+                    let plan = super::plan(&fs, &config).ok().map(|plan| {
+                        let total: usize = plan.dirs.iter().map(|(_, size)| size).sum();
+                        assert_eq!(total, plan.freed);
+                        match objective {
+                            Objective::SmallestTotal => (0, plan.freed),
+                            Objective::FewestDirs => (plan.dirs.len(), plan.freed),
+                        }
+                    });
+                    assert_eq!(expected, plan, "{:?} {:?} {}", objective, protected, needed);
+                }
+            }
+        }
+    }
+}
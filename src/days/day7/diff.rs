@@ -0,0 +1,211 @@
+use super::{FsNode, FsTree};
+use serde::Serialize;
+use serde_json;
+use std::fmt;
+use tree::NodeId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Resized,
+    /// Same size, but a different content hash or link target
+    Modified,
+}
+
+/// Difference at one path between two filesystems. Added and removed directories stand
+/// for their whole contents, while resized directories are followed by what changed
+/// inside of them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub old: Option<FsNode>,
+    pub new: Option<FsNode>,
+    /// Change of the (total) size in bytes
+    pub delta: i64,
+}
+
+impl Change {
+    fn new(path: String, old: Option<&FsNode>, new: Option<&FsNode>) -> Self {
+        let size = |node: Option<&FsNode>| node.map_or(0, |node| node.size() as i64);
+        Change {
+            path,
+            kind: match (old, new) {
+                (None, _) => ChangeKind::Added,
+                (_, None) => ChangeKind::Removed,
+                _ if size(old) != size(new) => ChangeKind::Resized,
+                _ => ChangeKind::Modified,
+            },
+            old: old.cloned(),
+            new: new.cloned(),
+            delta: size(new) - size(old),
+        }
+    }
+}
+
+/// Formats a change like `~ dir /a 94853 -> 95000 (+147)`, or `* symlink /b a -> c`
+/// for a modification
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (None, Some(new)) => write!(f, "+ {} {} {}", new.kind(), self.path, new.size()),
+            (Some(old), None) => write!(f, "- {} {} {}", old.kind(), self.path, old.size()),
+            (Some(old), Some(new)) if self.kind == ChangeKind::Modified => write!(
+                f,
+                "* {} {} {} -> {}",
+                new.kind(),
+                self.path,
+                content(old),
+                content(new)
+            ),
+            (Some(old), Some(new)) => write!(
+                f,
+                "~ {} {} {} -> {} ({:+})",
+                new.kind(),
+                self.path,
+                old.size(),
+                new.size(),
+                self.delta
+            ),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// The hash of a file or the target of a link
+fn content(node: &FsNode) -> &str {
+    match node {
+        FsNode::File { hash: Some(hash), .. } => hash,
+        FsNode::Symlink(target) => target,
+        _ => "",
+    }
+}
+
+/// Whether two entries of the same kind and size differ. Files only differ if both
+/// hashes are known.
+fn modified(old: &FsNode, new: &FsNode) -> bool {
+    match (old, new) {
+        (
+            FsNode::File {
+                hash: Some(old_hash),
+                ..
+            },
+            FsNode::File {
+                hash: Some(new_hash),
+                ..
+            },
+        ) => old_hash != new_hash,
+        (FsNode::Symlink(old_target), FsNode::Symlink(new_target)) => old_target != new_target,
+        _ => false,
+    }
+}
+
+/// Compares two filesystems path by path, listing the changes in preorder: entries of
+/// the old filesystem in their order first, then the ones only found in the new one.
+/// An entry that changed its kind, e.g. from a file into a directory, is removed and added.
+pub fn diff(old: &FsTree, new: &FsTree) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_nodes(old, new, old.root(), new.root(), &mut changes);
+    changes
+}
+
+/// One change per line, as formatted by `Display`
+pub fn text(changes: &[Change]) -> String {
+    changes.iter().map(|change| format!("{}\n", change)).collect()
+}
+
+/// One JSON record per change and line
+pub fn json_lines(changes: &[Change]) -> String {
+    let mut lines = String::new();
+    for change in changes {
+        lines.push_str(&serde_json::to_string(change).expect("Changes are always serializable"));
+        lines.push('\n');
+    }
+    lines
+}
+
+fn diff_nodes(
+    old: &FsTree,
+    new: &FsTree,
+    old_id: NodeId,
+    new_id: NodeId,
+    changes: &mut Vec<Change>,
+) {
+    let path = |fs: &FsTree, id: NodeId| fs.path(id).expect("Compared nodes exist");
+    let (old_value, new_value) = (&old[old_id].value, &new[new_id].value);
+    if old_value.kind() != new_value.kind() {
+        changes.push(Change::new(path(old, old_id), Some(old_value), None));
+        changes.push(Change::new(path(new, new_id), None, Some(new_value)));
+        return;
+    }
+    if old_value.size() != new_value.size() || modified(old_value, new_value) {
+        changes.push(Change::new(
+            path(new, new_id),
+            Some(old_value),
+            Some(new_value),
+        ));
+    }
+
+    for child in old[old_id].children() {
+        match new.get_child(new_id, &old[*child].name) {
+            Some(new_child) => diff_nodes(old, new, *child, new_child, changes),
+            None => changes.push(Change::new(path(old, *child), Some(&old[*child].value), None)),
+        }
+    }
+    for child in new[new_id].children() {
+        if old.get_child(old_id, &new[*child].name).is_none() {
+            changes.push(Change::new(path(new, *child), None, Some(&new[*child].value)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use days::day7::load_filesystem;
+    use io::Input;
+
+    #[test]
+    fn test_diff() {
+        let old = load_filesystem(&Input::text(
+            "$ cd /\n$ ls\ndir a\n100 b.txt\n5 c\n$ cd a\n$ ls\n10 x\n20 y\n",
+        ))
+        .unwrap();
+        let new = load_filesystem(&Input::text(
+            "$ cd /\n$ ls\ndir a\ndir c\n$ cd a\n$ ls\n10 x\n25 y\n3 z\n$ cd ../c\n$ ls\n1 d\n",
+        ))
+        .unwrap();
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            "~ dir / 135 -> 39 (-96)\n\
+             ~ dir /a 30 -> 38 (+8)\n\
+             ~ file /a/y 20 -> 25 (+5)\n\
+             + file /a/z 3\n\
+             - file /b.txt 100\n\
+             - file /c 5\n\
+             + dir /c 1\n",
+            text(&changes)
+        );
+        assert_eq!(
+            r#"{"path":"/a/y","kind":"resized","old":{"File":{"size":20}},"new":{"File":{"size":25}},"delta":5}"#,
+            json_lines(&changes).lines().nth(2).unwrap()
+        );
+        assert!(diff(&new, &new).is_empty());
+
+        let old = load_filesystem(&Input::text(
+            "$ ls -l\nlrwxrwxrwx 1 elf elves 1 Dec  7 12:00 l -> a\n3 f\n$ md5sum f\nab  f\n",
+        ))
+        .unwrap();
+        let new = load_filesystem(&Input::text(
+            "$ ls -l\nlrwxrwxrwx 1 elf elves 1 Dec  7 12:00 l -> b\n3 f\n$ md5sum f\ncd  f\n",
+        ))
+        .unwrap();
+        assert_eq!(
+            "* symlink /l a -> b\n* file /f ab -> cd\n",
+            text(&diff(&old, &new))
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use super::{FsNode, FsTree};
+use error::{Error, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use tree::{self, NodeId, NodeError};
+
+/// Resolves a path like `Tree::resolve`, but follows symbolic links on the way, including
+/// the last segment. Link targets are relative to the directory of the link, and `..`
+/// always goes to the parent in the tree.
+pub fn resolve(fs: &FsTree, from: NodeId, path: &str) -> Result<NodeId> {
+    resolve_in(fs, from, path, true, &mut Vec::new())
+}
+
+/// Like `resolve`, but a symbolic link in the last segment is returned itself,
+/// as `rm`, `mv` and `ln` treat it
+pub fn resolve_link(fs: &FsTree, from: NodeId, path: &str) -> Result<NodeId> {
+    resolve_in(fs, from, path, false, &mut Vec::new())
+}
+
+/// `expanding` holds the links whose targets are being resolved, to detect cycles
+fn resolve_in(
+    fs: &FsTree,
+    from: NodeId,
+    path: &str,
+    follow_last: bool,
+    expanding: &mut Vec<NodeId>,
+) -> Result<NodeId> {
+    let mut node = if path.starts_with(tree::SEPARATOR) {
+        fs.root()
+    } else {
+        from
+    };
+    for name in path.split(tree::SEPARATOR) {
+        if name.is_empty() || name == "." {
+            continue;
+        }
+        node = follow(fs, node, expanding)?;
+        node = match name {
+            ".." => fs.up(node).ok_or_else(|| {
+                NodeError::NotFound(format!("'{}' goes above the root", path))
+            })?,
+            _ => fs
+                .get_child(node, name)
+                .ok_or_else(|| NodeError::NotFound(format!("'{}' not found", path)))?,
+        };
+    }
+    if follow_last {
+        node = follow(fs, node, expanding)?;
+    }
+    Ok(node)
+}
+
+fn follow(fs: &FsTree, node: NodeId, expanding: &mut Vec<NodeId>) -> Result<NodeId> {
+    match &fs[node].value {
+        FsNode::Symlink(target) => {
+            if expanding.contains(&node) {
+                return Err(Error::invalid_state(format!(
+                    "symbolic link cycle through '{}'",
+                    fs.path(node)?
+                )));
+            }
+            expanding.push(node);
+            let parent = fs.up(node).expect("The root is a directory");
+            let resolved = resolve_in(fs, parent, target, true, expanding)?;
+            expanding.pop();
+            Ok(resolved)
+        }
+        _ => Ok(node),
+    }
+}
+
+/// Files that are likely copies of each other
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Duplicates {
+    pub name: String,
+    pub size: usize,
+    /// The content hash, if any of the files has a known one
+    pub hash: Option<String>,
+    pub paths: Vec<String>,
+}
+
+impl Duplicates {
+    /// Bytes that deleting all but one copy would free
+    pub fn wasted(&self) -> usize {
+        self.size * (self.paths.len() - 1)
+    }
+}
+
+/// Formats the group as a heading followed by one indented path per line
+impl fmt::Display for Duplicates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} copies of {} bytes",
+            self.name,
+            self.paths.len(),
+            self.size
+        )?;
+        if let Some(hash) = &self.hash {
+            write!(f, ", hash {}", hash)?;
+        }
+        for path in &self.paths {
+            write!(f, "\n  {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Groups the files with the same name and size, most wasted bytes first. Hard links to
+/// the same inode are one file. Groups whose files have different known hashes are split
+/// by hash, leaving out the files without one.
+pub fn duplicates(fs: &FsTree) -> Vec<Duplicates> {
+    type File = (String, Option<String>);
+    let mut groups: BTreeMap<(String, usize), Vec<File>> = BTreeMap::new();
+    let mut inodes = HashSet::new();
+    for visit in fs.preorder(fs.root()) {
+        let node = &fs[visit.id];
+        if let FsNode::File { size, inode, hash } = &node.value {
+            if inode.is_some_and(|inode| !inodes.insert(inode)) {
+                continue;
+            }
+            groups
+                .entry((node.name.clone(), *size))
+                .or_default()
+                .push((fs.path(visit.id).expect("Visited nodes exist"), hash.clone()));
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for ((name, size), files) in groups {
+        let hashes: HashSet<&String> = files.iter().filter_map(|(_, hash)| hash.as_ref()).collect();
+        let mut by_hash: BTreeMap<Option<String>, Vec<String>> = BTreeMap::new();
+        for (path, hash) in files.iter() {
+            match hash {
+                Some(_) if hashes.len() > 1 => by_hash.entry(hash.clone()),
+                None if hashes.len() > 1 => continue,
+                _ => by_hash.entry(hashes.iter().next().map(|h| h.to_string())),
+            }
+            .or_default()
+            .push(path.clone());
+        }
+        for (hash, paths) in by_hash {
+            if paths.len() > 1 {
+                duplicates.push(Duplicates {
+                    name: name.clone(),
+                    size,
+                    hash,
+                    paths,
+                });
+            }
+        }
+    }
+    duplicates.sort_by_key(|d| std::cmp::Reverse(d.wasted()));
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use days::day7::load_filesystem;
+    use io::Input;
+
+    const LOG: &str = "$ cd /\n\
+                       $ ls -li\n\
+                       11 drwxr-xr-x 2 elf elves 4096 Dec  7 12:00 a\n\
+                       12 -rw-r--r-- 2 elf elves  100 Dec  7 12:00 big\n\
+                       13 lrwxrwxrwx 1 elf elves    1 Dec  7 12:00 here -> a\n\
+                       14 lrwxrwxrwx 1 elf elves    4 Dec  7 12:00 loop -> loop\n\
+                       $ ln big a/big\n\
+                       $ ln -s ../here/b a/up\n\
+                       $ cd here\n\
+                       $ ls\n\
+                       dir b\n\
+                       7 notes\n\
+                       $ cd up\n\
+                       $ ls\n\
+                       7 notes\n\
+                       $ sha256sum /a/notes /a/b/notes\n\
+                       4f2a  /a/notes\n\
+                       4f2a  /a/b/notes\n";
+
+    #[test]
+    fn test_links() {
+        let fs = load_filesystem(&Input::text(LOG)).unwrap();
+        let root = fs.root();
+        let b = fs.resolve(root, "a/b").unwrap();
+        assert_eq!(b, resolve(&fs, root, "here/up").unwrap());
+        assert_eq!(b, resolve(&fs, root, "/a/up/../../here/b").unwrap());
+        assert_ne!(b, resolve_link(&fs, root, "here/up").unwrap());
+
+        let err = resolve(&fs, root, "loop/x").unwrap_err();
+        assert_eq!(
+            "Invalid state: symbolic link cycle through '/loop'",
+            err.to_string()
+        );
+        assert!(resolve(&fs, root, "here/nowhere").is_err());
+
+        // the hard linked file counts once
+        assert_eq!(114, fs[root].value.size());
+        assert_eq!(114, fs[fs.resolve(root, "a").unwrap()].value.size());
+        assert_eq!(7, fs[b].value.size());
+        assert_eq!(
+            "file, size=100, inode=12",
+            fs[fs.resolve(root, "a/big").unwrap()].value.to_string()
+        );
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let fs = load_filesystem(&Input::text(LOG)).unwrap();
+        let duplicates = duplicates(&fs);
+        assert_eq!(1, duplicates.len());
+        assert_eq!(7, duplicates[0].wasted());
+        assert_eq!(
+            "notes: 2 copies of 7 bytes, hash 4f2a\n  /a/b/notes\n  /a/notes",
+            duplicates[0].to_string()
+        );
+
+        let fs = load_filesystem(&Input::text(
+            "$ ls\ndir a\n5 x\n$ cd a\n$ ls\n5 x\n$ md5sum x /x\nff  x\nee  /x\n",
+        ))
+        .unwrap();
+        assert!(super::duplicates(&fs).is_empty());
+    }
+}
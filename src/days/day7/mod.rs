@@ -0,0 +1,835 @@
+use error::{Error, Result};
+use io::{self, Input};
+use serde::{Deserialize, Serialize};
+use solver::{Answer, Part, Solver};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::mem;
+use tree::{self, NodeId, Tree};
+
+pub mod cleanup;
+pub mod diff;
+pub mod links;
+pub mod shell;
+
+pub type FsTree = Tree<FsNode>;
+
+pub struct Day7;
+
+impl Solver for Day7 {
+    fn day(&self) -> u32 {
+        7
+    }
+
+    fn input(&self) -> &'static str {
+        "terminal.log"
+    }
+
+    fn part1(&self, input: &Input) -> Result<Answer> {
+        let fs = load_filesystem(input)?;
+
+        let mut sum: usize = 0;
+        for visit in fs.preorder(fs.root()) {
+            if let FsNode::Dir(size) = fs[visit.id].value {
+                if size <= 100000 {
+                    sum += size
+                }
+            }
+        }
+        Ok(sum.into())
+    }
+
+    fn part2(&self, input: &Input) -> Result<Answer> {
+        let fs = load_filesystem(input)?;
+
+        // the smallest single directory that frees up enough space, if there is one
+        let config = cleanup::Config {
+            objective: cleanup::Objective::FewestDirs,
+            ..cleanup::Config::default()
+        };
+        if cleanup::needed(&fs, &config)? == 0 {
+            let smallest = fs
+                .preorder(fs.root())
+                .map(|visit| &fs[visit.id].value)
+                .filter(|node| node.is_dir())
+                .map(|node| node.size())
+                .min();
+            return Ok(smallest.unwrap_or(0).into());
+        }
+        let plan = cleanup::plan(&fs, &config)?;
+        if plan.dirs.len() != 1 {
+            return Err(Error::not_found(format!(
+                "no single directory frees {} bytes",
+                plan.needed
+            )));
+        }
+        Ok(plan.freed.into())
+    }
+
+    /// The reconstructed filesystem, drawn like `tree`
+    fn render(&self, input: &Input) -> Option<Result<String>> {
+        Some(load_filesystem(input).map(|fs| fs.render(fs.root(), None)))
+    }
+
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        match part {
+            Part::One => format!(
+                "Sum of directory sizes with a total lower than 100000: {}",
+                answer
+            ),
+            Part::Two => format!(
+                "Size of smallest directory that frees up enough space: {}",
+                answer
+            ),
+        }
+    }
+}
+
+/// Reconstructs the filesystem described by the terminal log
+pub fn load_filesystem(input: &Input) -> Result<FsTree> {
+    load_filesystem_with_warnings(input).map(|(fs, _)| fs)
+}
+
+/// Like `load_filesystem`, but also returns the unknown commands and the unexpected output
+/// that were skipped, each located at its line in the log
+pub fn load_filesystem_with_warnings(input: &Input) -> Result<(FsTree, Vec<Error>)> {
+    let mut fs: FsTree = Tree::new("/", FsNode::Dir(0));
+
+    let warnings = build_tree_from_log(input, &mut fs)?;
+    Ok((fs, warnings))
+}
+
+/// A file or directory, together with its (total) size, or a symbolic link
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FsNode {
+    /// A file, with its inode number if listed by `ls -i` or hard linked with `ln`,
+    /// and the hash of its content if printed by a checksum command
+    File {
+        size: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        inode: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hash: Option<String>,
+    },
+    /// A directory, with the total size of its files. Hard links to one inode count once.
+    Dir(usize),
+    /// A symbolic link to a path, relative to the directory of the link
+    Symlink(String),
+}
+
+impl FsNode {
+    /// A file of which nothing but the size is known
+    pub fn file(size: usize) -> Self {
+        FsNode::File {
+            size,
+            inode: None,
+            hash: None,
+        }
+    }
+
+    /// Size of a file, or total size of a directory. Symbolic links take no space.
+    pub fn size(&self) -> usize {
+        match self {
+            FsNode::File { size, .. } | FsNode::Dir(size) => *size,
+            FsNode::Symlink(_) => 0,
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(self, FsNode::Dir(_))
+    }
+
+    /// `file`, `dir` or `symlink`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FsNode::File { .. } => "file",
+            FsNode::Dir(_) => "dir",
+            FsNode::Symlink(_) => "symlink",
+        }
+    }
+}
+
+impl fmt::Display for FsNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FsNode::File { size, inode, hash } => {
+                write!(f, "file, size={}", size)?;
+                if let Some(inode) = inode {
+                    write!(f, ", inode={}", inode)?;
+                }
+                if let Some(hash) = hash {
+                    write!(f, ", hash={}", hash)?;
+                }
+                Ok(())
+            }
+            FsNode::Dir(size) => write!(f, "dir, size={}", size),
+            FsNode::Symlink(target) => write!(f, "symlink -> {}", target),
+        }
+    }
+}
+
+enum Command {
+    Ls,
+    Cd(String),
+    Mkdir { paths: Vec<String>, parents: bool },
+    Rm { paths: Vec<String>, recursive: bool, force: bool },
+    Touch(Vec<String>),
+    Mv { from: String, to: String },
+    Ln { target: String, link: String, symbolic: bool },
+    /// a checksum command like `sha256sum`
+    Checksum,
+    Unknown(String),
+}
+
+/// How to treat the lines following a command, up to the next one
+#[derive(PartialEq)]
+enum Output {
+    /// entries of the working directory, as printed by `ls` or `ls -l`
+    Listing,
+    /// `HASH  PATH` lines of a checksum command
+    Checksums,
+    /// the output of an unknown command, which was already warned about
+    Skipped,
+    /// commands that print nothing on success
+    Unexpected,
+}
+
+/// Replay of a terminal log: the working directory, what the current output lines
+/// describe, and the problems that were skipped
+struct Session {
+    cwd: NodeId,
+    output: Output,
+    warnings: Vec<Error>,
+    /// inode number for the next file hard linked without a listed one, counting down
+    /// to stay clear of the numbers listed by `ls -i`
+    next_inode: u64,
+}
+
+fn is_dir(node: &tree::Node<FsNode>) -> bool {
+    matches!(node.value, FsNode::Dir(_))
+}
+
+fn build_tree_from_log(input: &Input, fs: &mut FsTree) -> Result<Vec<Error>> {
+    let mut session = Session {
+        cwd: fs.root(),
+        output: Output::Listing,
+        warnings: Vec::new(),
+        next_inode: u64::MAX,
+    };
+    let mut number = 0;
+    io::foreach_line(input, |line| {
+        number += 1;
+        if let Some(warning) = parse_line(line, fs, &mut session)? {
+            session.warnings.push(warning.at(&input.name(), number));
+        }
+        Ok(())
+    })?;
+
+    add_up_sizes(fs);
+    Ok(session.warnings)
+}
+
+/// Sets the total size of every directory. Files with an inode found more than once in
+/// the tree are hard links, which are counted once per directory.
+fn add_up_sizes(fs: &mut FsTree) {
+    let root = fs.root();
+    // size and number of links of every inode
+    let mut links: HashMap<u64, (usize, usize)> = HashMap::new();
+    for visit in fs.preorder(root) {
+        if let FsNode::File {
+            size,
+            inode: Some(inode),
+            ..
+        } = fs[visit.id].value
+        {
+            links.entry(inode).or_insert((size, 0)).1 += 1;
+        }
+    }
+    links.retain(|_, (_, count)| *count > 1);
+
+    // per directory, the size without the hard linked files, and the hard linked inodes
+    // below it with their total size, merged from the children
+    let mut subtotals: HashMap<NodeId, (usize, HashSet<u64>, usize)> = HashMap::new();
+    fs.traverse_apply_if(root, is_dir, |fs, node| {
+        let (mut unlinked, mut inodes, mut linked) = (0, HashSet::new(), 0);
+        for child in fs[node].children() {
+            match &fs[*child].value {
+                FsNode::File {
+                    size,
+                    inode: Some(inode),
+                    ..
+                } if links.contains_key(inode) => {
+                    if inodes.insert(*inode) {
+                        linked += size;
+                    }
+                }
+                FsNode::File { size, .. } => unlinked += size,
+                FsNode::Dir(_) => {
+                    let (child_unlinked, mut child_inodes, mut child_linked) = subtotals
+                        .remove(child)
+                        .expect("Directories are visited after their subdirectories");
+                    unlinked += child_unlinked;
+                    // merge the smaller set into the larger one
+                    if child_inodes.len() > inodes.len() {
+                        mem::swap(&mut inodes, &mut child_inodes);
+                        mem::swap(&mut linked, &mut child_linked);
+                    }
+                    for inode in child_inodes {
+                        if inodes.insert(inode) {
+                            linked += links[&inode].0;
+                        }
+                    }
+                }
+                FsNode::Symlink(_) => {}
+            }
+        }
+        fs[node].value = FsNode::Dir(unlinked + linked);
+        subtotals.insert(node, (unlinked, inodes, linked));
+    });
+}
+
+/// Applies one line of the log to the filesystem. Returns a warning for lines that
+/// are skipped, and an error for lines that contradict the filesystem.
+fn parse_line(line: &str, fs: &mut FsTree, session: &mut Session) -> Result<Option<Error>> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    match parts.first().copied() {
+        Some("$") => {
+            let command = parse_command(&parts[1..])?;
+            session.output = match command {
+                // entries following a `cd` have always been accepted as its listing
+                Command::Ls | Command::Cd(_) => Output::Listing,
+                Command::Checksum => Output::Checksums,
+                Command::Unknown(_) => Output::Skipped,
+                _ => Output::Unexpected,
+            };
+            run_command(command, fs, session)
+        }
+        Some(_) => match session.output {
+            Output::Listing => parse_entry(line, &parts, fs, session.cwd).map(|_| None),
+            Output::Checksums => parse_checksum(line, fs, session.cwd),
+            Output::Skipped => Ok(None),
+            Output::Unexpected => Ok(Some(Error::parse(format!(
+                "Ignoring unexpected output: {}",
+                line
+            )))),
+        },
+        None => Err(Error::parse("Unexpected empty line.")),
+    }
+}
+
+/// Adds an entry of an `ls` listing, either `dir NAME`, `SIZE NAME`, or in the long format
+/// `-rw-r--r-- 1 user group SIZE Dec  7 12:00 NAME`, optionally preceded by the inode
+/// number as printed by `ls -li`. `ls` of a directory listed before must not add its
+/// entries twice.
+fn parse_entry(line: &str, parts: &[&str], fs: &mut FsTree, node: NodeId) -> Result<()> {
+    match parts {
+        ["total", size] if size.parse::<usize>().is_ok() => {}
+        ["dir", name] => add_entry(fs, node, name, FsNode::Dir(0))?,
+        [size, name] if size.parse::<usize>().is_ok() => {
+            add_entry(fs, node, name, FsNode::file(size.parse()?))?
+        }
+        [mode, ..] if is_file_mode(mode) => parse_long_entry(line, parts, None, fs, node)?,
+        [inode, mode, ..] if inode.parse::<u64>().is_ok() && is_file_mode(mode) => {
+            parse_long_entry(line, &parts[1..], inode.parse().ok(), fs, node)?
+        }
+        [start, ..] if start.parse::<usize>().is_ok() => {
+            return Err(Error::parse(format!(
+                "Detected file with size {}, but no name: {}",
+                start, line
+            )))
+        }
+        ["dir", ..] => return Err(Error::parse(format!("Detected dir, but no name: {}", line))),
+        _ => {
+            return Err(Error::parse(format!(
+                "Unrecognized start of string: {}",
+                parts[0]
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Adds an entry in the long format, given the line without the inode number
+fn parse_long_entry(
+    line: &str,
+    parts: &[&str],
+    inode: Option<u64>,
+    fs: &mut FsTree,
+    node: NodeId,
+) -> Result<()> {
+    if parts.len() < 9 {
+        return Err(Error::parse(format!(
+            "Expected mode, links, owner, group, size, date and name: {}",
+            line
+        )));
+    }
+    let name = parts[8..].join(" ");
+    if parts[0].starts_with('d') {
+        add_entry(fs, node, &name, FsNode::Dir(0))?;
+    } else if parts[0].starts_with('l') {
+        let (name, target) = name
+            .split_once(" -> ")
+            .ok_or_else(|| Error::parse(format!("Expected the target of the link: {}", line)))?;
+        add_entry(fs, node, name, FsNode::Symlink(target.to_string()))?;
+    } else {
+        let size = parts[4]
+            .parse::<usize>()
+            .map_err(|_| Error::parse(format!("Invalid size '{}' of {}", parts[4], name)))?;
+        let file = FsNode::File {
+            size,
+            inode,
+            hash: None,
+        };
+        add_entry(fs, node, &name, file)?;
+    }
+    Ok(())
+}
+
+/// Adds a listed entry. `.` and `..`, as listed by `ls -a`, are skipped. Listing a known
+/// file keeps what else is known about it, and sets its size if it was empty, e.g. after
+/// `touch`. Entries that contradict the known ones are an error.
+fn add_entry(fs: &mut FsTree, node: NodeId, name: &str, value: FsNode) -> Result<()> {
+    if name == "." || name == ".." {
+        return Ok(());
+    }
+    let existing = match fs.get_child(node, name) {
+        Some(existing) => existing,
+        None => {
+            fs.add(node, name, value)?;
+            return Ok(());
+        }
+    };
+    let value = match (&fs[existing].value, value) {
+        (FsNode::Dir(_), FsNode::Dir(_)) => return Ok(()),
+        (
+            FsNode::File { size, inode, hash },
+            FsNode::File {
+                size: new_size,
+                inode: new_inode,
+                hash: new_hash,
+            },
+        ) => {
+            if *size != 0 && *size != new_size {
+                return Err(Error::invalid_state(format!(
+                    "'{}' is listed with {} bytes, but has {}",
+                    fs.path(existing)?,
+                    new_size,
+                    size
+                )));
+            }
+            FsNode::File {
+                size: new_size,
+                inode: new_inode.or(*inode),
+                hash: new_hash.or_else(|| hash.clone().filter(|_| *size == new_size)),
+            }
+        }
+        (FsNode::Symlink(_), link @ FsNode::Symlink(_)) => link,
+        (existing_value, value) => {
+            return Err(Error::invalid_state(format!(
+                "'{}' is listed as a {}, but is a {}",
+                fs.path(existing)?,
+                value.kind(),
+                existing_value.kind()
+            )))
+        }
+    };
+    fs.set_value(existing, value)?;
+    Ok(())
+}
+
+/// Sets the hash of a file from a line like `d41d8cd98f00b204e9800998ecf8427e  PATH`,
+/// where binary mode marks the path with `*` instead of the second space
+fn parse_checksum(line: &str, fs: &mut FsTree, cwd: NodeId) -> Result<Option<Error>> {
+    let (hash, path) = match line.split_once(' ') {
+        Some((hash, rest)) if !hash.is_empty() && rest.len() > 1 => (hash, &rest[1..]),
+        _ => {
+            return Ok(Some(Error::parse(format!(
+                "Ignoring unexpected output: {}",
+                line
+            ))))
+        }
+    };
+    let node = links::resolve(fs, cwd, path)?;
+    match fs[node].value.clone() {
+        FsNode::File { size, inode, .. } => fs.set_value(
+            node,
+            FsNode::File {
+                size,
+                inode,
+                hash: Some(hash.to_string()),
+            },
+        )?,
+        _ => {
+            return Err(Error::invalid_state(format!(
+                "'{}' has a checksum, but is not a file",
+                path
+            )))
+        }
+    }
+    Ok(None)
+}
+
+/// Whether a word is the mode column of `ls -l`, e.g. `drwxr-xr-x`, `lrwxrwxrwx` or
+/// `-rw-r--r--@`
+fn is_file_mode(word: &str) -> bool {
+    let mut chars = word.chars();
+    matches!(chars.next(), Some('-') | Some('d') | Some('l'))
+        && chars
+            .by_ref()
+            .take(9)
+            .all(|c| "rwxsStT-".contains(c))
+        && word.len() >= 10
+}
+
+fn parse_command(parts: &[&str]) -> Result<Command> {
+    let (name, args) = match parts.split_first() {
+        Some((name, args)) => (*name, args),
+        None => return Err(Error::parse("Missing command after '$'")),
+    };
+    let (flags, operands): (Vec<&str>, Vec<&str>) = args
+        .iter()
+        .partition(|arg| arg.len() > 1 && arg.starts_with('-'));
+    let has_flag = |flag: char| flags.iter().any(|f| f[1..].contains(flag));
+    let unsupported = |allowed: &str| {
+        flags
+            .iter()
+            .flat_map(|f| f[1..].chars())
+            .find(|c| !allowed.contains(*c))
+    };
+    let operands: Vec<String> = operands.iter().map(|s| s.to_string()).collect();
+
+    match name {
+        // listing flags do not matter, `ls -l` lines are recognized by themselves
+        "ls" if operands.is_empty() => Ok(Command::Ls),
+        "cd" if flags.is_empty() && operands.len() == 1 => Ok(Command::Cd(operands[0].clone())),
+        // the paths come with the output
+        "md5sum" | "sha1sum" | "sha256sum" | "sha512sum" | "b2sum" if flags.is_empty() => {
+            Ok(Command::Checksum)
+        }
+        "mkdir" | "rm" | "touch" | "mv" | "ln" => {
+            let allowed = match name {
+                "mkdir" => "p",
+                "rm" => "rf",
+                "ln" => "s",
+                _ => "",
+            };
+            if let Some(flag) = unsupported(allowed) {
+                return Err(Error::parse(format!("Unsupported flag of {}: -{}", name, flag)));
+            }
+            let pair = name == "mv" || name == "ln";
+            if operands.is_empty() || (pair && operands.len() != 2) {
+                return Err(Error::parse(format!("Missing operands: {}", parts.join(" "))));
+            }
+            Ok(match name {
+                "mkdir" => Command::Mkdir {
+                    paths: operands,
+                    parents: has_flag('p'),
+                },
+                "rm" => Command::Rm {
+                    paths: operands,
+                    recursive: has_flag('r'),
+                    force: has_flag('f'),
+                },
+                "touch" => Command::Touch(operands),
+                "ln" => Command::Ln {
+                    target: operands[0].clone(),
+                    link: operands[1].clone(),
+                    symbolic: has_flag('s'),
+                },
+                _ => Command::Mv {
+                    from: operands[0].clone(),
+                    to: operands[1].clone(),
+                },
+            })
+        }
+        "ls" | "cd" => Err(Error::parse(format!(
+            "Unsupported arguments: {}",
+            parts.join(" ")
+        ))),
+        _ => Ok(Command::Unknown(parts.join(" "))),
+    }
+}
+
+/// Applies a command to the filesystem, returning a warning for unknown commands
+fn run_command(command: Command, fs: &mut FsTree, session: &mut Session) -> Result<Option<Error>> {
+    match command {
+        Command::Ls => {}
+        Command::Cd(dir) => session.cwd = cd(fs, session.cwd, &dir)?,
+        Command::Mkdir { paths, parents } => {
+            for path in paths {
+                if parents {
+                    let dir = fs.create_path(session.cwd, &path, FsNode::Dir(0))?;
+                    check_dir(fs, dir, &path)?;
+                } else {
+                    let (parent, name) = split_path(fs, session.cwd, &path)?;
+                    fs.add(parent, name, FsNode::Dir(0))?;
+                }
+            }
+        }
+        Command::Rm {
+            paths,
+            recursive,
+            force,
+        } => {
+            for path in paths {
+                let node = match links::resolve_link(fs, session.cwd, &path) {
+                    Ok(node) => node,
+                    Err(_) if force => continue,
+                    Err(e) => return Err(e),
+                };
+                if is_dir(&fs[node]) && !recursive {
+                    return Err(Error::invalid_state(format!(
+                        "'{}' is a directory, it can only be removed with -r",
+                        path
+                    )));
+                }
+                if fs.is_descendant(session.cwd, node) {
+                    return Err(Error::invalid_state(format!(
+                        "'{}' contains the working directory",
+                        path
+                    )));
+                }
+                fs.remove(node)?;
+            }
+        }
+        Command::Touch(paths) => {
+            for path in paths {
+                let (parent, name) = split_path(fs, session.cwd, &path)?;
+                if fs.get_child(parent, name).is_none() {
+                    fs.add(parent, name, FsNode::file(0))?;
+                }
+            }
+        }
+        Command::Mv { from, to } => mv(fs, session.cwd, &from, &to)?,
+        Command::Ln {
+            target,
+            link,
+            symbolic,
+        } => ln(fs, session, &target, &link, symbolic)?,
+        Command::Checksum => {}
+        Command::Unknown(command) => {
+            return Ok(Some(Error::parse(format!(
+                "Skipping unknown command: {}",
+                command
+            ))))
+        }
+    }
+    Ok(None)
+}
+
+fn cd(fs: &FsTree, node: NodeId, dir: &str) -> Result<NodeId> {
+    let target = match dir {
+        ".." => fs
+            .up(node)
+            .ok_or_else(|| Error::invalid_state("cannot go up from the root directory"))?,
+        _ => links::resolve(fs, node, dir)?,
+    };
+    check_dir(fs, target, dir)?;
+    Ok(target)
+}
+
+/// Moves or renames like `mv`: into `to` if it is a directory, otherwise to the path `to`,
+/// replacing a file or link there
+fn mv(fs: &mut FsTree, cwd: NodeId, from: &str, to: &str) -> Result<()> {
+    let node = links::resolve_link(fs, cwd, from)?;
+    let target = match links::resolve(fs, cwd, to) {
+        Ok(dir) if is_dir(&fs[dir]) => Ok(dir),
+        _ => links::resolve_link(fs, cwd, to),
+    };
+    let (parent, name) = match target {
+        Ok(target) if is_dir(&fs[target]) => (target, fs[node].name.clone()),
+        Ok(target) if target == node => return Ok(()),
+        Ok(_) if is_dir(&fs[node]) => {
+            return Err(Error::invalid_state(format!(
+                "cannot overwrite the file '{}' with the directory '{}'",
+                to, from
+            )))
+        }
+        Ok(target) => {
+            let parent = fs.up(target).expect("The root is a directory");
+            let name = fs[target].name.clone();
+            fs.remove(target)?;
+            (parent, name)
+        }
+        Err(_) => {
+            let (parent, name) = split_path(fs, cwd, to)?;
+            (parent, name.to_string())
+        }
+    };
+    if let Some(existing) = fs.get_child(parent, &name) {
+        if existing != node {
+            return Err(Error::invalid_state(format!(
+                "cannot overwrite '{}' with '{}'",
+                fs.path(existing)?,
+                from
+            )));
+        }
+    }
+    fs.move_subtree(node, parent)?;
+    fs.rename(node, &name)?;
+    Ok(())
+}
+
+/// Links like `ln`: at the path `link`, or into `link` if it is a directory. A hard link
+/// shares the inode of its file, which gets a new number if it had none.
+fn ln(fs: &mut FsTree, session: &mut Session, target: &str, link: &str, symbolic: bool) -> Result<()> {
+    let cwd = session.cwd;
+    let (parent, name) = match links::resolve(fs, cwd, link) {
+        Ok(dir) if is_dir(&fs[dir]) => {
+            let name = target.trim_end_matches(tree::SEPARATOR);
+            let name = name.rsplit(tree::SEPARATOR).next().unwrap_or(name);
+            if name.is_empty() || name == "." || name == ".." {
+                return Err(Error::parse(format!("Invalid path: {}", target)));
+            }
+            (dir, name.to_string())
+        }
+        Ok(_) => return Err(Error::invalid_state(format!("'{}' already exists", link))),
+        Err(_) => {
+            let (parent, name) = split_path(fs, cwd, link)?;
+            (parent, name.to_string())
+        }
+    };
+
+    let value = if symbolic {
+        FsNode::Symlink(target.to_string())
+    } else {
+        let file = links::resolve(fs, cwd, target)?;
+        match fs[file].value.clone() {
+            FsNode::File { size, inode, hash } => {
+                let inode = inode.unwrap_or_else(|| {
+                    session.next_inode -= 1;
+                    session.next_inode
+                });
+                let value = FsNode::File {
+                    size,
+                    inode: Some(inode),
+                    hash,
+                };
+                fs.set_value(file, value.clone())?;
+                value
+            }
+            _ => {
+                return Err(Error::invalid_state(format!(
+                    "'{}' is not a file, it cannot be hard linked",
+                    target
+                )))
+            }
+        }
+    };
+    fs.add(parent, &name, value)?;
+    Ok(())
+}
+
+/// Splits a path into its existing parent directory and the name of its last segment
+fn split_path<'a>(fs: &FsTree, cwd: NodeId, path: &'a str) -> Result<(NodeId, &'a str)> {
+    let trimmed = path.trim_end_matches(tree::SEPARATOR);
+    let (parent, name) = match trimmed.rsplit_once(tree::SEPARATOR) {
+        Some(("", name)) => (fs.root(), name),
+        Some((parent, name)) => (links::resolve(fs, cwd, parent)?, name),
+        None => (cwd, trimmed),
+    };
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(Error::parse(format!("Invalid path: {}", path)));
+    }
+    check_dir(fs, parent, path)?;
+    Ok((parent, name))
+}
+
+fn check_dir(fs: &FsTree, node: NodeId, path: &str) -> Result<()> {
+    if is_dir(&fs[node]) {
+        Ok(())
+    } else {
+        Err(Error::invalid_state(format!("'{}' is not a directory", path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(fs: &FsTree) -> Vec<String> {
+        fs.preorder(fs.root())
+            .map(|visit| format!("{} {}", fs.path(visit.id).unwrap(), fs[visit.id].value))
+            .collect()
+    }
+
+    #[test]
+    fn test_extended_log() {
+        let log = "$ cd /\n\
+                   $ ls -la\n\
+                   total 16\n\
+                   drwxr-xr-x 3 elf elves 4096 Dec  7 12:00 .\n\
+                   drwxr-xr-x 3 elf elves 4096 Dec  7 12:00 ..\n\
+                   drwxr-xr-x 2 elf elves 4096 Dec  7 12:00 a\n\
+                   -rw-r--r-- 1 elf elves  100 Dec  7  2022 my notes.txt\n\
+                   -rw-r--r-- 1 elf elves   42 Dec  7 12:00 todo\n\
+                   $ mkdir -p a/b/c x\n\
+                   $ touch a/b/c/empty a/b/c/../f\n\
+                   $ cd a/b/../b/c\n\
+                   $ cat /todo\n\
+                   buy more stars\n\
+                   $ ls\n\
+                   20 empty\n\
+                   $ mv /todo ../../../x\n\
+                   $ mv ../f /x/g\n\
+                   $ rm -rf /x/todo /missing\n\
+                   $ cd /\n\
+                   $ rm -r x\n\
+                   $ mv a/b/c c\n\
+                   $ mkdir d\n\
+                   oops\n";
+        let (fs, warnings) = load_filesystem_with_warnings(&Input::text(log)).unwrap();
+        assert_eq!(
+            vec![
+                "/ dir, size=120",
+                "/a dir, size=0",
+                "/a/b dir, size=0",
+                "/my notes.txt file, size=100",
+                "/c dir, size=20",
+                "/c/empty file, size=20",
+                "/d dir, size=0",
+            ],
+            names(&fs)
+        );
+        let warnings: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            vec![
+                "<text>:12: Skipping unknown command: cat /todo",
+                "<text>:23: Ignoring unexpected output: oops",
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_part2_with_enough_free_space() {
+        let log = "$ cd /\n$ ls\ndir a\n30 f\n$ cd a\n$ ls\n12 g\n";
+        assert_eq!(Answer::from(12usize), Day7.part2(&Input::text(log)).unwrap());
+    }
+
+    #[test]
+    fn test_log_errors() {
+        let errors = [
+            ("$ cd nowhere\n", "<text>:1: Not found: 'nowhere' not found"),
+            ("$ touch f\n$ cd f\n", "<text>:2: Invalid state: 'f' is not a directory"),
+            (
+                "$ mkdir a\n$ rm a\n",
+                "<text>:2: Invalid state: 'a' is a directory, it can only be removed with -r",
+            ),
+            ("$ mkdir a/b\n", "<text>:1: Not found: 'a' not found"),
+            ("$ rm -i a\n", "<text>:1: Unsupported flag of rm: -i"),
+            (
+                "$ ls\n10 x\n$ ls\ndir x\n",
+                "<text>:4: Invalid state: '/x' is listed as a dir, but is a file",
+            ),
+            (
+                "$ ls\n10 x\n$ ls\n12 x\n",
+                "<text>:4: Invalid state: '/x' is listed with 12 bytes, but has 10",
+            ),
+        ];
+        for (log, expected) in errors.iter() {
+            let err = load_filesystem(&Input::text(log)).unwrap_err();
+            assert_eq!(*expected, err.to_string());
+        }
+    }
+}
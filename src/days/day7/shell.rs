@@ -0,0 +1,396 @@
+use super::{links, FsNode, FsTree};
+use error::{Error, Result};
+use repl;
+use std::io::{self, BufRead, Write};
+use tree::NodeId;
+
+const HELP: &str = "\
+cd [PATH]                        change the working directory, `/` without a path
+ls [-l] [PATH]                   list a directory, with kinds and sizes for -l
+pwd                              print the working directory
+du [-h] [-s] [PATH]              total size of every directory, human readable with -h,
+                                 only PATH itself with -s
+find [PATH] [-type f|d|l] [-size [+|-]N[k|M|G]]
+                                 paths of the entries larger than (+), smaller than (-)
+                                 or exactly N bytes large
+tree [-L DEPTH] [PATH]           draw the directory, down to DEPTH levels
+dups                             files with the same name and size, most wasted space first
+help                             show this help
+exit                             leave the shell
+";
+
+/// Condition on the entries printed by `find`
+type Filter = Box<dyn Fn(&FsNode) -> bool>;
+
+/// A minimal shell to explore a reconstructed filesystem
+pub struct Shell {
+    fs: FsTree,
+    cwd: NodeId,
+}
+
+impl Shell {
+    pub fn new(fs: FsTree) -> Self {
+        let cwd = fs.root();
+        Shell { fs, cwd }
+    }
+
+    /// Absolute path of the working directory
+    pub fn pwd(&self) -> String {
+        self.fs
+            .path(self.cwd)
+            .expect("The working directory is never removed")
+    }
+
+    /// Executes one command line and returns its output
+    pub fn execute(&mut self, line: &str) -> Result<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Ok(String::new()),
+        };
+        match command {
+            "cd" => self.cd(args),
+            "ls" => self.ls(args),
+            "pwd" => Ok(format!("{}\n", self.pwd())),
+            "du" => self.du(args),
+            "find" => self.find(args),
+            "tree" => self.tree(args),
+            "dups" => self.dups(args),
+            "help" => Ok(HELP.to_string()),
+            _ => Err(Error::parse(format!("Unknown command: {}", command))),
+        }
+    }
+
+    /// Executes commands line by line until `exit` or the end of the input.
+    /// Failing commands are reported on `err` and do not stop the shell.
+    /// Returns the number of failed commands.
+    pub fn run<R, W, E>(&mut self, input: R, out: &mut W, err: &mut E, prompt: bool) -> io::Result<usize>
+    where
+        R: BufRead,
+        W: Write,
+        E: Write,
+    {
+        let prompt = Some(|shell: &Shell| format!("{}$ ", shell.pwd())).filter(|_| prompt);
+        repl::run(self, input, out, err, prompt, Shell::execute)
+    }
+
+    /// The node at `path`, or the working directory without a path
+    fn lookup(&self, path: Option<&str>) -> Result<NodeId> {
+        match path {
+            Some(path) => links::resolve(&self.fs, self.cwd, path),
+            None => Ok(self.cwd),
+        }
+    }
+
+    fn cd(&mut self, args: &[&str]) -> Result<String> {
+        let (_, paths) = parse_flags(args, "")?;
+        let target = match paths.as_slice() {
+            [] => self.fs.root(),
+            [path] => links::resolve(&self.fs, self.cwd, path)?,
+            _ => return Err(Error::parse("cd: too many arguments")),
+        };
+        if !self.fs[target].value.is_dir() {
+            return Err(Error::invalid_state(format!(
+                "cd: '{}' is not a directory",
+                paths[0]
+            )));
+        }
+        self.cwd = target;
+        Ok(String::new())
+    }
+
+    fn ls(&self, args: &[&str]) -> Result<String> {
+        let (flags, paths) = parse_flags(args, "l")?;
+        let node = self.lookup(single_path("ls", &paths)?)?;
+        let entries: Vec<NodeId> = if self.fs[node].value.is_dir() {
+            self.fs[node].children().to_vec()
+        } else {
+            vec![node]
+        };
+
+        let width = entries
+            .iter()
+            .map(|id| self.fs[*id].value.size().to_string().len())
+            .max()
+            .unwrap_or(0);
+        let mut output = String::new();
+        for id in entries {
+            let entry = &self.fs[id];
+            if flags.contains(&'l') {
+                let kind = match entry.value {
+                    FsNode::File { .. } => '-',
+                    FsNode::Dir(_) => 'd',
+                    FsNode::Symlink(_) => 'l',
+                };
+                output.push_str(&format!(
+                    "{} {:>width$} ",
+                    kind,
+                    entry.value.size(),
+                    width = width
+                ));
+            }
+            output.push_str(&entry.name);
+            if let (true, FsNode::Symlink(target)) = (flags.contains(&'l'), &entry.value) {
+                output.push_str(" -> ");
+                output.push_str(target);
+            }
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    fn du(&self, args: &[&str]) -> Result<String> {
+        let (flags, paths) = parse_flags(args, "hs")?;
+        let start = self.lookup(single_path("du", &paths)?)?;
+        let entries: Vec<NodeId> = if flags.contains(&'s') || !self.fs[start].value.is_dir() {
+            vec![start]
+        } else {
+            self.fs
+                .postorder(start)
+                .map(|visit| visit.id)
+                .filter(|id| self.fs[*id].value.is_dir())
+                .collect()
+        };
+
+        let mut output = String::new();
+        for id in entries {
+            let size = self.fs[id].value.size();
+            let size = if flags.contains(&'h') {
+                human_size(size)
+            } else {
+                size.to_string()
+            };
+            output.push_str(&format!("{}\t{}\n", size, self.fs.path(id)?));
+        }
+        Ok(output)
+    }
+
+    fn find(&self, args: &[&str]) -> Result<String> {
+        let mut start: Option<&str> = None;
+        let mut filters: Vec<Filter> = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "-type" => match args.next().copied() {
+                    Some("f") => filters.push(Box::new(|node| matches!(node, FsNode::File { .. }))),
+                    Some("d") => filters.push(Box::new(|node| node.is_dir())),
+                    Some("l") => filters.push(Box::new(|node| matches!(node, FsNode::Symlink(_)))),
+                    other => {
+                        return Err(Error::parse(format!(
+                            "find: -type expects 'f', 'd' or 'l', got {:?}",
+                            other
+                        )))
+                    }
+                },
+                "-size" => {
+                    let size = args
+                        .next()
+                        .ok_or_else(|| Error::parse("find: -size expects a size"))?;
+                    filters.push(size_filter(size)?);
+                }
+                path if !path.starts_with('-') && start.is_none() => start = Some(path),
+                other => return Err(Error::parse(format!("find: unexpected argument: {}", other))),
+            }
+        }
+
+        let start = self.lookup(start)?;
+        let mut output = String::new();
+        for visit in self.fs.preorder(start) {
+            if filters.iter().all(|filter| filter(&self.fs[visit.id].value)) {
+                output.push_str(&self.fs.path(visit.id)?);
+                output.push('\n');
+            }
+        }
+        Ok(output)
+    }
+
+    fn tree(&self, args: &[&str]) -> Result<String> {
+        let mut depth: Option<usize> = None;
+        let mut paths: Vec<&str> = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "-L" => {
+                    let value = args.next().ok_or_else(|| Error::parse("tree: -L expects a depth"))?;
+                    depth = Some(value.parse().map_err(|_| {
+                        Error::parse(format!("tree: invalid depth: {}", value))
+                    })?);
+                }
+                flag if flag.starts_with('-') => {
+                    return Err(Error::parse(format!("tree: unknown flag: {}", flag)))
+                }
+                path => paths.push(path),
+            }
+        }
+        let start = self.lookup(single_path("tree", &paths)?)?;
+        Ok(self.fs.render(start, depth))
+    }
+
+    fn dups(&self, args: &[&str]) -> Result<String> {
+        if !args.is_empty() {
+            return Err(Error::parse("dups: too many arguments"));
+        }
+        Ok(links::duplicates(&self.fs)
+            .iter()
+            .map(|duplicates| format!("{}\n", duplicates))
+            .collect())
+    }
+}
+
+/// Splits arguments into single letter flags, which may be combined as in `-lh`, and operands
+fn parse_flags<'a>(args: &[&'a str], allowed: &str) -> Result<(Vec<char>, Vec<&'a str>)> {
+    let mut flags = Vec::new();
+    let mut operands = Vec::new();
+    for arg in args {
+        match arg.strip_prefix('-') {
+            Some(letters) if !letters.is_empty() => {
+                for flag in letters.chars() {
+                    if !allowed.contains(flag) {
+                        return Err(Error::parse(format!("Unknown flag: -{}", flag)));
+                    }
+                    flags.push(flag);
+                }
+            }
+            _ => operands.push(*arg),
+        }
+    }
+    Ok((flags, operands))
+}
+
+fn single_path<'a>(command: &str, paths: &[&'a str]) -> Result<Option<&'a str>> {
+    match paths {
+        [] => Ok(None),
+        [path] => Ok(Some(path)),
+        _ => Err(Error::parse(format!("{}: too many arguments", command))),
+    }
+}
+
+/// Parses a `find -size` argument: `+N` matches more, `-N` less and `N` exactly N bytes,
+/// where N may carry a `k`, `M` or `G` suffix for powers of 1024. Unlike find(1), sizes
+/// are compared in bytes rather than rounded up to whole units.
+fn size_filter(arg: &str) -> Result<Filter> {
+    let invalid = || Error::parse(format!("find: invalid size: {}", arg));
+    let (sign, number) = match arg.chars().next() {
+        Some(sign @ '+') | Some(sign @ '-') => (Some(sign), &arg[1..]),
+        _ => (None, arg),
+    };
+    let (number, unit) = match number.chars().last() {
+        Some('k') => (&number[..number.len() - 1], 1 << 10),
+        Some('M') => (&number[..number.len() - 1], 1 << 20),
+        Some('G') => (&number[..number.len() - 1], 1 << 30),
+        _ => (number, 1),
+    };
+    let limit = number
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit))
+        .ok_or_else(invalid)?;
+    Ok(match sign {
+        Some('+') => Box::new(move |node: &FsNode| node.size() > limit),
+        Some(_) => Box::new(move |node: &FsNode| node.size() < limit),
+        None => Box::new(move |node: &FsNode| node.size() == limit),
+    })
+}
+
+/// Formats a size like `du -h`: powers of 1024, rounded up, with one decimal below 10
+fn human_size(size: usize) -> String {
+    let units = ['K', 'M', 'G', 'T'];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64;
+    let mut unit = 0;
+    value /= 1024.0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        let rounded = (value * 10.0).ceil() / 10.0;
+        if rounded < 10.0 {
+            return format!("{:.1}{}", rounded, units[unit]);
+        }
+    }
+    format!("{}{}", value.ceil(), units[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use days::day7::load_filesystem;
+    use io::Input;
+
+    const LOG: &str = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n$ ls\n\
+                       dir e\n29116 f\n2557 g\n62596 h.lst\n$ cd e\n$ ls\n584 i\n$ cd ..\n$ cd ..\n\
+                       $ cd d\n$ ls\n4060174 j\n8033020 d.log\n5626152 d.ext\n7214296 k\n";
+
+    fn shell() -> Shell {
+        Shell::new(load_filesystem(&Input::text(LOG)).unwrap())
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut shell = shell();
+        assert_eq!("", shell.execute("cd a/e").unwrap());
+        assert_eq!("/a/e\n", shell.execute("pwd").unwrap());
+        assert_eq!("i\n", shell.execute("ls").unwrap());
+        shell.execute("cd").unwrap();
+        assert_eq!(
+            "d    94853 a\n- 14848514 b.txt\n-  8504156 c.dat\nd 24933642 d\n",
+            shell.execute("ls -l").unwrap()
+        );
+        assert_eq!("584\t/a/e\n94853\t/a\n", shell.execute("du a").unwrap());
+        assert_eq!("47M\t/\n", shell.execute("du -sh").unwrap());
+        assert_eq!(
+            "/\n/b.txt\n/c.dat\n/d\n",
+            shell.execute("find -size +8M").unwrap()
+        );
+        assert_eq!(
+            "/a\n/a/e\n",
+            shell.execute("find / -type d -size -100k").unwrap()
+        );
+        assert_eq!("/a/e\n/a/e/i\n", shell.execute("find -size -585").unwrap());
+        assert_eq!("/a/e/i\n", shell.execute("find a/e -type f -size +583").unwrap());
+        assert_eq!("", shell.execute("find a/e -size +584").unwrap());
+        let err = shell.execute("find -size +20000000000G").unwrap_err();
+        assert_eq!("find: invalid size: +20000000000G", err.to_string());
+        assert_eq!(
+            "e (dir, size=584)\n└── i (file, size=584)\n",
+            shell.execute("tree -L 1 a/e").unwrap()
+        );
+
+        let err = shell.execute("cd b.txt").unwrap_err();
+        assert_eq!(
+            "Invalid state: cd: 'b.txt' is not a directory",
+            err.to_string()
+        );
+        assert!(shell.execute("cd nope").is_err());
+        assert!(shell.execute("rm -rf /").is_err());
+        assert_eq!("/\n", shell.execute("pwd").unwrap());
+    }
+
+    #[test]
+    fn test_run_script() {
+        let script = "cd d\nls\nfrobnicate\npwd\nexit\npwd\n";
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let failed = shell().run(script.as_bytes(), &mut out, &mut err, false).unwrap();
+        assert_eq!(1, failed);
+        assert_eq!(
+            "j\nd.log\nd.ext\nk\n/d\n",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(
+            "Unknown command: frobnicate\n",
+            String::from_utf8(err).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!("1023", human_size(1023));
+        assert_eq!("1.0K", human_size(1024));
+        assert_eq!("1.1K", human_size(1025));
+        assert_eq!("10K", human_size(10 * 1024 - 1));
+        assert_eq!("93K", human_size(94853));
+    }
+}
@@ -1,45 +1,56 @@
 use direction::Direction;
-use io;
+use error::{self, Error};
+use io::{self, Input};
+use solver::{Answer, Part, Solver};
 use std::cmp::Ordering;
 use std::collections::HashSet;
-use std::convert::From;
+use std::convert::TryFrom;
 use std::fmt;
 
-pub fn day9(args: &[String]) {
-    if args.len() != 1 {
-        panic!("Expecting exactly one arg to day9, which is a valid file path.");
+pub struct Day9;
+
+impl Solver for Day9 {
+    fn day(&self) -> u32 {
+        9
     }
 
-    let visited_first_rope = visited_coords(&args[0], 2);
+    fn input(&self) -> &'static str {
+        "rope_motions.txt"
+    }
 
-    println!(
-        "Number of coordinates visited by the tail of a rope with {} knots: {}",
-        2,
-        visited_first_rope.len()
-    );
-    // for c in visited_first_rope {
-    //     println!("{}", c);
-    // }
+    fn part1(&self, input: &Input) -> error::Result<Answer> {
+        Ok(visited_coords(input, 2)?.len().into())
+    }
 
-    let visited_second_rope = visited_coords(&args[0], 10);
+    fn part2(&self, input: &Input) -> error::Result<Answer> {
+        Ok(visited_coords(input, 10)?.len().into())
+    }
 
-    println!(
-        "Number of coordinates visited by the tail of a rope with {} knots: {}",
-        10,
-        visited_second_rope.len()
-    );
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        let knots = match part {
+            Part::One => 2,
+            Part::Two => 10,
+        };
+        format!(
+            "Number of coordinates visited by the tail of a rope with {} knots: {}",
+            knots, answer
+        )
+    }
 }
 
-fn visited_coords(moveset_file: &str, knots: usize) -> HashSet<Coord> {
+fn visited_coords(input: &Input, knots: usize) -> error::Result<HashSet<Coord>> {
     let mut visited: HashSet<Coord> = HashSet::new();
     let mut rope = vec![Coord { x: 0, y: 0 }; knots];
     visited.insert(rope.last().unwrap().clone());
 
-    io::foreach_line(moveset_file, |line| {
+    io::foreach_line(input, |line| {
         if !line.is_empty() {
-            let (direction, steps) = line.trim().split_once(' ').unwrap();
-            let steps: usize = steps.parse().unwrap();
-            let direction: Direction = direction.into();
+            let (direction, steps) = line
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| Error::parse(format!("Invalid motion: {}", line)))?;
+            let steps: usize = steps.parse()?;
+            let direction = Direction::try_from(direction)?;
 
             for _ in 0..steps {
                 rope.first_mut().unwrap().step(&direction);
@@ -54,8 +65,9 @@ fn visited_coords(moveset_file: &str, knots: usize) -> HashSet<Coord> {
                 visited.insert(rope.last().unwrap().clone());
             }
         }
-    });
-    visited
+        Ok(())
+    })?;
+    Ok(visited)
 }
 
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
@@ -84,9 +96,7 @@ impl Coord {
         let x_adjacent = self.x == other.x - 1 || self.x == other.x + 1;
         let y_adjacent = self.y == other.y - 1 || self.y == other.y + 1;
 
-        x_adjacent && y_adjacent
-            || x_adjacent && self.y == other.y
-            || y_adjacent && self.x == other.x
+        x_adjacent && (y_adjacent || self.y == other.y) || y_adjacent && self.x == other.x
     }
 
     fn touches(&self, other: &Self) -> bool {
@@ -107,14 +117,16 @@ fn step(d1: i32, d2: i32) -> i32 {
     }
 }
 
-impl From<&str> for Direction {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Direction {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "U" => Direction::Up,
-            "D" => Direction::Down,
-            "L" => Direction::Left,
-            "R" => Direction::Right,
-            _ => panic!("Unrecognized direction: {}", s),
+            "U" => Ok(Direction::Up),
+            "D" => Ok(Direction::Down),
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            _ => Err(Error::parse(format!("Unrecognized direction: {}", s))),
         }
     }
 }
@@ -1,12 +1,55 @@
-pub mod day1;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
+use solver::Solver;
+
+/// Declares the day modules and registers their solvers, in calendar order.
+macro_rules! register_days {
+    ($($module:ident => $solver:ident),* $(,)*) => {
+        $(pub mod $module;)*
+
+        /// All known solvers, ordered by day
+        pub fn registry() -> Vec<Box<dyn Solver>> {
+            vec![$(Box::new($module::$solver)),*]
+        }
+    };
+}
+
+register_days! {
+    day1 => Day1,
+    day2 => Day2,
+    day3 => Day3,
+    day4 => Day4,
+    day5 => Day5,
+    day6 => Day6,
+    day7 => Day7,
+    day8 => Day8,
+    day9 => Day9,
+    day10 => Day10,
+    day11 => Day11,
+    day12 => Day12,
+}
+
+/// Looks up a solver by its name ("day3") or plain day number ("3")
+pub fn find(name: &str) -> Option<Box<dyn Solver>> {
+    registry()
+        .into_iter()
+        .find(|solver| solver.name() == name || solver.day().to_string() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered_by_day() {
+        let days: Vec<u32> = registry().iter().map(|s| s.day()).collect();
+        let expected: Vec<u32> = (1..=days.len() as u32).collect();
+        assert_eq!(expected, days);
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(3, find("day3").unwrap().day());
+        assert_eq!(12, find("12").unwrap().day());
+        assert!(find("day0").is_none());
+        assert!(find("dayX").is_none());
+    }
+}
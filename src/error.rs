@@ -0,0 +1,238 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Position of a problem within a puzzle input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    /// Name of the input, e.g. the file path
+    pub source: String,
+    /// Line number, starting at 1
+    pub line: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.source, self.line)
+    }
+}
+
+/// Errors reported by the puzzle solvers and the modules they use
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be opened or read
+    Io {
+        source: String,
+        line: Option<usize>,
+        err: io::Error,
+    },
+    /// The input was read, but its content could not be understood
+    Parse {
+        location: Option<Location>,
+        /// Column within the line, starting at 1
+        column: Option<usize>,
+        msg: String,
+    },
+    /// The input was understood, but describes a situation the puzzle does not allow
+    InvalidState {
+        location: Option<Location>,
+        msg: String,
+    },
+    /// Something the puzzle asks for does not exist
+    NotFound {
+        location: Option<Location>,
+        what: String,
+    },
+}
+
+impl Error {
+    pub fn io(source: &str, err: io::Error) -> Self {
+        Error::Io {
+            source: source.to_string(),
+            line: None,
+            err,
+        }
+    }
+
+    pub fn parse<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Error::Parse {
+            location: None,
+            column: None,
+            msg: msg.into(),
+        }
+    }
+
+    pub fn invalid_state<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Error::InvalidState {
+            location: None,
+            msg: msg.into(),
+        }
+    }
+
+    pub fn not_found<S>(what: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Error::NotFound {
+            location: None,
+            what: what.into(),
+        }
+    }
+
+    /// Attaches the column at which a parse error occured, unless it is already known
+    pub fn column(self, column: usize) -> Self {
+        match self {
+            Error::Parse {
+                location,
+                column: None,
+                msg,
+            } => Error::Parse {
+                location,
+                column: Some(column),
+                msg,
+            },
+            e => e,
+        }
+    }
+
+    /// Attaches the position in the input at which the error occured, unless it is already known
+    pub fn at(self, source: &str, line: usize) -> Self {
+        let location = Some(Location {
+            source: source.to_string(),
+            line,
+        });
+        match self {
+            Error::Io {
+                source: s,
+                line: None,
+                err,
+            } => Error::Io {
+                source: s,
+                line: Some(line),
+                err,
+            },
+            Error::Parse {
+                location: None,
+                column,
+                msg,
+            } => Error::Parse {
+                location,
+                column,
+                msg,
+            },
+            Error::InvalidState {
+                location: None,
+                msg,
+            } => Error::InvalidState { location, msg },
+            Error::NotFound {
+                location: None,
+                what,
+            } => Error::NotFound { location, what },
+            e => e,
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::Io { .. } => None,
+            Error::Parse { location, .. }
+            | Error::InvalidState { location, .. }
+            | Error::NotFound { location, .. } => location.as_ref(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(location) = self.location() {
+            write!(f, "{}", location)?;
+            if let Error::Parse {
+                column: Some(column),
+                ..
+            } = self
+            {
+                write!(f, ":{}", column)?;
+            }
+            write!(f, ": ")?;
+        } else if let Error::Parse {
+            column: Some(column),
+            ..
+        } = self
+        {
+            write!(f, "column {}: ", column)?;
+        }
+        match self {
+            Error::Io {
+                source,
+                line: Some(line),
+                err,
+            } => write!(f, "{}:{}: {}", source, line, err),
+            Error::Io { source, err, .. } => write!(f, "{}: {}", source, err),
+            Error::Parse { msg, .. } => write!(f, "{}", msg),
+            Error::InvalidState { msg, .. } => write!(f, "Invalid state: {}", msg),
+            Error::NotFound { what, .. } => write!(f, "Not found: {}", what),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(err: ParseIntError) -> Self {
+        Error::parse(format!("Invalid number: {}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_keeps_first_location() {
+        let err = Error::parse("bad").at("a.txt", 3).at("b.txt", 5);
+        assert_eq!("a.txt:3: bad", err.to_string());
+
+        let err = Error::io("a.txt", io::Error::new(io::ErrorKind::InvalidData, "oops"));
+        assert_eq!("a.txt: oops", err.to_string());
+        assert_eq!("a.txt:2: oops", err.at("b.txt", 2).to_string());
+    }
+
+    #[test]
+    fn test_display_variants() {
+        let err = Error::parse("unexpected 'x'").column(4).at("a.txt", 2);
+        assert_eq!("a.txt:2:4: unexpected 'x'", err.to_string());
+        assert_eq!("column 4: bad", Error::parse("bad").column(4).to_string());
+        assert_eq!(
+            "a.txt:7: Invalid state: cannot go up",
+            Error::invalid_state("cannot go up")
+                .at("a.txt", 7)
+                .to_string()
+        );
+        assert_eq!("Not found: marker", Error::not_found("marker").to_string());
+    }
+
+    #[test]
+    fn test_from_parse_int_error() {
+        let err: Error = "x1".parse::<i32>().unwrap_err().into();
+        assert_eq!(
+            "<text>:1: Invalid number: invalid digit found in string",
+            err.at("<text>", 1).to_string()
+        );
+    }
+}
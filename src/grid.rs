@@ -0,0 +1,373 @@
+use direction::Direction;
+use error::{Error, Result};
+use io::{self, Input};
+use std::fmt;
+use std::mem;
+use std::ops::{Index, IndexMut};
+
+/// Position of a cell: `x` is the column, `y` the row, counted from the top left corner
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Point { x, y }
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+/// Offset of one step in a direction. Rows grow downwards, so `Up` decreases `y`.
+fn offset(dir: &Direction) -> (isize, isize) {
+    match dir {
+        Direction::Up => (0, -1),
+        Direction::Down => (0, 1),
+        Direction::Left => (-1, 0),
+        Direction::Right => (1, 0),
+    }
+}
+
+/// Offsets of the 8 surrounding cells, clockwise starting at the top left
+const OFFSETS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// Rectangular 2D map of cells, stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with every cell set to `value`, unless its cells would take more
+    /// bytes than a `Vec` can hold
+    pub fn new(width: usize, height: usize, value: T) -> Result<Self>
+    where
+        T: Clone,
+    {
+        let len = width
+            .checked_mul(height)
+            .and_then(|len| {
+                let bytes = len.checked_mul(mem::size_of::<T>())?;
+                Some(len).filter(|_| bytes <= isize::MAX as usize)
+            })
+            .ok_or_else(|| {
+                Error::invalid_state(format!("a grid of {}x{} cells is too large", width, height))
+            })?;
+        Ok(Grid {
+            width,
+            height,
+            cells: vec![value; len],
+        })
+    }
+
+    /// Builds a grid from rows, which must all have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::parse(format!(
+                    "Row {} has {} cells, expected {}",
+                    y + 1,
+                    row.len(),
+                    width
+                )));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a character map, one row per non-empty line, converting every character with `f`.
+    /// Errors point at the line and column of the offending character.
+    pub fn parse<F>(input: &Input, mut f: F) -> Result<Self>
+    where
+        F: FnMut(Point, char) -> Result<T>,
+    {
+        let mut width: Option<usize> = None;
+        let mut height: usize = 0;
+        let mut cells: Vec<T> = Vec::new();
+        io::foreach_nonempty_line(input, |line| {
+            let mut row_len = 0;
+            for (x, c) in line.chars().enumerate() {
+                cells.push(f(Point::new(x, height), c).map_err(|e| e.column(x + 1))?);
+                row_len += 1;
+            }
+            match width {
+                Some(w) if w != row_len => {
+                    return Err(Error::parse(format!(
+                        "Expected a row of length {}, got {}",
+                        w, row_len
+                    )))
+                }
+                _ => width = Some(row_len),
+            }
+            height += 1;
+            Ok(())
+        })?;
+        if height == 0 {
+            return Err(Error::parse("Empty map").at(&input.name(), 1));
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether signed coordinates lie inside the grid
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.contains(p.x as isize, p.y as isize) {
+            Some(&self.cells[p.y * self.width + p.x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p.x as isize, p.y as isize) {
+            Some(&mut self.cells[p.y * self.width + p.x])
+        } else {
+            None
+        }
+    }
+
+    fn offset_point(&self, p: Point, (dx, dy): (isize, isize)) -> Option<Point> {
+        let x = p.x as isize + dx;
+        let y = p.y as isize + dy;
+        if self.contains(x, y) {
+            Some(Point::new(x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    /// The neighbouring point in a direction, if it is inside the grid
+    pub fn step(&self, p: Point, dir: &Direction) -> Option<Point> {
+        self.offset_point(p, offset(dir))
+    }
+
+    /// The up to 4 horizontally and vertically adjacent points
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::iter().filter_map(move |dir| self.step(p, dir))
+    }
+
+    /// The up to 8 adjacent points, including the diagonals
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        OFFSETS8
+            .iter()
+            .filter_map(move |offset| self.offset_point(p, *offset))
+    }
+
+    /// The points from `p` in a direction up to the edge of the grid, excluding `p` itself
+    pub fn ray(&self, p: Point, dir: &Direction) -> impl Iterator<Item = Point> + '_ {
+        let offset = offset(dir);
+        let mut current = p;
+        std::iter::from_fn(move || {
+            current = self.offset_point(current, offset)?;
+            Some(current)
+        })
+    }
+
+    /// Cells of row `y`, left to right, or none if the grid is not that high
+    pub fn row(&self, y: usize) -> &[T] {
+        if y >= self.height {
+            return &[];
+        }
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// Cells of column `x`, top to bottom, or none if the grid is not that wide
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(height)
+    }
+
+    /// All points, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// All cells with their position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> {
+        self.points().zip(self.cells.iter_mut())
+    }
+
+    /// All cells, row by row, without their position
+    pub fn values_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Position of the first cell, row by row, that satisfies the predicate
+    pub fn position<P>(&self, mut predicate: P) -> Option<Point>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().find(|(_, v)| predicate(v)).map(|(p, _)| p)
+    }
+
+    /// Creates a grid of the same shape by converting every cell
+    pub fn map<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| {
+            panic!(
+                "{} is outside of the {}x{} grid",
+                p, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{} is outside of the {}x{} grid", p, width, height))
+    }
+}
+
+/// Renders the cells row by row, without separators and without a trailing newline
+impl<T> fmt::Display for Grid<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(text: &str) -> Grid<u32> {
+        Grid::parse(&Input::text(text), |_, c| {
+            c.to_digit(10)
+                .ok_or_else(|| Error::parse(format!("Not a digit: {}", c)))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = digits("123\n456\n");
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&6), grid.get(Point::new(2, 1)));
+        assert_eq!(None, grid.get(Point::new(3, 0)));
+        assert_eq!("123\n456", grid.to_string());
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(0, grid.column(3).count());
+        assert_eq!(&[4, 5, 6], grid.row(1));
+        assert!(grid.row(2).is_empty());
+        assert!(Grid::new(usize::MAX, 2, 0).is_err());
+        assert_eq!(2, Grid::new(1, 2, 0).unwrap().height());
+
+        let err = Grid::parse(&Input::text("12\n3x\n"), |_, c| {
+            c.to_digit(10)
+                .ok_or_else(|| Error::parse(format!("Not a digit: {}", c)))
+        })
+        .unwrap_err();
+        assert_eq!("<text>:2:2: Not a digit: x", err.to_string());
+
+        let err = Grid::parse(&Input::text("12\n3\n"), |_, c| Ok(c)).unwrap_err();
+        assert_eq!(
+            "<text>:2: Expected a row of length 2, got 1",
+            err.to_string()
+        );
+        let err = Grid::parse(&Input::text("\n"), |_, c| Ok(c)).unwrap_err();
+        assert_eq!("<text>:1: Empty map", err.to_string());
+    }
+
+    #[test]
+    fn test_neighbours_and_rays() {
+        let grid = digits("123\n456\n789\n");
+        let values = |points: Vec<Point>| -> Vec<u32> { points.iter().map(|p| grid[*p]).collect() };
+
+        let corner = Point::new(0, 0);
+        assert_eq!(vec![4, 2], values(grid.neighbours(corner).collect()));
+        assert_eq!(vec![2, 5, 4], values(grid.neighbours8(corner).collect()));
+        assert_eq!(8, grid.neighbours8(Point::new(1, 1)).count());
+
+        let center = Point::new(1, 1);
+        assert_eq!(vec![2], values(grid.ray(center, &Direction::Up).collect()));
+        assert_eq!(
+            vec![2, 3],
+            values(grid.ray(corner, &Direction::Right).collect())
+        );
+        assert_eq!(0, grid.ray(corner, &Direction::Left).count());
+        assert_eq!(None, grid.step(corner, &Direction::Up));
+    }
+}
@@ -1,60 +1,202 @@
+use error::{Error, Result};
 use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use std::io::{self, BufRead, Read};
+use std::path::{Path, PathBuf};
 
-pub fn foreach_nonempty_line<P, F>(filename: P, mut f: F)
+/// Source of puzzle input: a file on disk or text held in memory.
+/// Standard input is read into memory once, so that it can be consumed by both parts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    File(PathBuf),
+    Stdin(String),
+    Text(String),
+}
+
+impl Input {
+    pub fn file<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Input::File(path.as_ref().to_path_buf())
+    }
+
+    pub fn text(text: &str) -> Self {
+        Input::Text(text.to_string())
+    }
+
+    /// Interprets a command line argument: `-` means stdin, anything else is a file path
+    pub fn from_arg(arg: &str) -> Result<Self> {
+        if arg == "-" {
+            let mut text = String::new();
+            io::stdin()
+                .lock()
+                .read_to_string(&mut text)
+                .map_err(|e| Error::io("<stdin>", e))?;
+            Ok(Input::Stdin(text))
+        } else {
+            Ok(Input::file(arg))
+        }
+    }
+
+    pub fn from_reader<R>(mut reader: R) -> Result<Self>
+    where
+        R: Read,
+    {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .map_err(|e| Error::io("<reader>", e))?;
+        Ok(Input::Text(text))
+    }
+
+    /// Name of the input as shown in diagnostics
+    pub fn name(&self) -> String {
+        match self {
+            Input::File(path) => path.display().to_string(),
+            Input::Stdin(_) => "<stdin>".to_string(),
+            Input::Text(_) => "<text>".to_string(),
+        }
+    }
+
+    /// Opens the input for buffered reading
+    pub fn reader(&self) -> Result<Box<dyn BufRead + '_>> {
+        match self {
+            Input::File(path) => {
+                let file = File::open(path).map_err(|e| Error::io(&self.name(), e))?;
+                Ok(Box::new(io::BufReader::new(file)))
+            }
+            Input::Stdin(text) | Input::Text(text) => Ok(Box::new(text.as_bytes())),
+        }
+    }
+}
+
+pub fn foreach_nonempty_line<F>(input: &Input, mut f: F) -> Result<()>
 where
-    P: AsRef<Path>,
-    F: FnMut(&str),
+    F: FnMut(&str) -> Result<()>,
 {
-    foreach_line(filename, |line| {
+    foreach_line(input, |line| {
         if !line.is_empty() {
-            f(line);
+            f(line)?;
         }
-    });
+        Ok(())
+    })
 }
 
-pub fn foreach_line<P, F>(filename: P, mut f: F)
+/// Calls `f` on every line of the input.
+/// Errors of reading or of `f` are annotated with the input name and line number.
+pub fn foreach_line<F>(input: &Input, f: F) -> Result<()>
 where
-    P: AsRef<Path>,
-    F: FnMut(&str),
+    F: FnMut(&str) -> Result<()>,
 {
-    if let Ok(lines) = read_lines(filename) {
-        for line in lines {
-            if let Ok(line) = line {
-                f(line.as_str());
-            }
-        }
+    foreach_line_in(input.reader()?, &input.name(), f)
+}
+
+/// Calls `f` on every line of any buffered source, using `source` as its name in errors
+pub fn foreach_line_in<R, F>(reader: R, source: &str, mut f: F) -> Result<()>
+where
+    R: BufRead,
+    F: FnMut(&str) -> Result<()>,
+{
+    for (number, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| Error::io(source, e).at(source, number + 1))?;
+        f(line.as_str()).map_err(|e| e.at(source, number + 1))?;
     }
+    Ok(())
 }
 
-pub fn foreach_linegroup<P, F>(filename: P, group_size: usize, mut f: F)
+/// Calls `f` on consecutive groups of `group_size` lines. Trailing lines that do not
+/// fill a whole group are ignored.
+pub fn foreach_linegroup<F>(input: &Input, group_size: usize, mut f: F) -> Result<()>
 where
-    P: AsRef<Path>,
-    F: FnMut(&Vec<String>),
+    F: FnMut(&Vec<String>) -> Result<()>,
 {
     let mut group: Vec<String> = Vec::new();
-    foreach_line(filename, |line| {
+    foreach_line(input, |line| {
         group.push(line.to_string());
         if group.len() == group_size {
-            f(&group);
+            f(&group)?;
             group.clear();
         }
-    });
+        Ok(())
+    })
 }
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-pub fn get_reader<P>(filename: P) -> io::Result<io::BufReader<File>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file))
+    #[test]
+    fn test_foreach_line_text() {
+        let input = Input::text("a\n\nb\nc");
+        let mut lines: Vec<String> = Vec::new();
+        foreach_line(&input, |line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(vec!["a", "", "b", "c"], lines);
+
+        lines.clear();
+        foreach_nonempty_line(&input, |line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(vec!["a", "b", "c"], lines);
+    }
+
+    #[test]
+    fn test_foreach_linegroup() {
+        let input = Input::text("a\nb\nc\nd\ne\nf\ng\n");
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        foreach_linegroup(&input, 3, |group| {
+            groups.push(group.clone());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(vec![vec!["a", "b", "c"], vec!["d", "e", "f"]], groups);
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let input = Input::from_reader("x\ny\n".as_bytes()).unwrap();
+        assert_eq!(Input::text("x\ny\n"), input);
+        assert_eq!(
+            Input::file("files/x.txt"),
+            Input::from_arg("files/x.txt").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_errors_carry_location() {
+        let input = Input::text("1\n2\nx\n4");
+        let err = foreach_line(&input, |line| {
+            line.parse::<u32>()?;
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(
+            "<text>:3: Invalid number: invalid digit found in string",
+            err.to_string()
+        );
+
+        let input = Input::file("does/not/exist.txt");
+        let err = foreach_line(&input, |_| Ok(())).unwrap_err();
+        assert!(err.to_string().starts_with("does/not/exist.txt: "));
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_an_error() {
+        // an unreadable line is reported, rather than skipped or silently ending the input
+        let bytes: &[u8] = b"ok\n\xff\xfe\nlater\n";
+        let mut lines: Vec<String> = Vec::new();
+        let err = foreach_line_in(bytes, "bytes", |line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.to_string().starts_with("bytes:2: "));
+        assert!(matches!(err, Error::Io { line: Some(2), .. }));
+        assert_eq!(vec!["ok"], lines);
+    }
 }
@@ -1,4 +1,3 @@
-use std::fs::File;
 use std::io::{self, BufRead};
 
 // iterator that moves over a bufreader in windows / kmers
@@ -21,7 +20,7 @@ where
         if self.first {
             self.first = false;
             match self.reader.read_exact(&mut self.window) {
-                Ok(_) => Some(self.window.clone()),
+                Ok(_) => Some(self.window),
                 Err(_) => None,
             }
         } else {
@@ -30,12 +29,10 @@ where
                 Ok(_) => {
                     // change the array in place
                     let window = &mut self.window;
-                    for i in 0..N - 1 {
-                        window[i] = window[i + 1];
-                    }
+                    window.rotate_left(1);
                     window[N - 1] = buf[0];
-                    // return a clone
-                    Some(window.clone())
+                    // return a copy
+                    Some(*window)
                 }
                 _ => None,
             }
@@ -43,11 +40,14 @@ where
     }
 }
 
-pub fn make_window_iter<const N: usize>(
-    reader: &mut io::BufReader<File>,
-) -> Result<WindowIter<io::BufReader<File>, N>, io::Error> {
-    let iter: WindowIter<io::BufReader<File>, N> = WindowIter {
-        reader: reader,
+pub fn make_window_iter<T, const N: usize>(
+    reader: &mut T,
+) -> Result<WindowIter<'_, T, N>, io::Error>
+where
+    T: BufRead,
+{
+    let iter: WindowIter<T, N> = WindowIter {
+        reader,
         window: [0; N],
         first: true,
     };
@@ -0,0 +1,22 @@
+//! Solutions to the Advent of Code 2022 puzzles, together with the
+//! input handling, iterators and tree structure they are built on.
+
+pub mod algorithm;
+pub mod answers;
+pub mod bench;
+pub mod days;
+pub mod direction;
+pub mod error;
+pub mod grid;
+pub mod io;
+pub mod iter;
+pub mod repl;
+pub mod runner;
+pub mod solver;
+pub mod tree;
+
+extern crate itertools;
+extern crate serde;
+extern crate serde_json;
+extern crate serde_stacker;
+extern crate toml;
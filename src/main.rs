@@ -1,37 +1,498 @@
 use std::env;
-mod algorithm;
-mod days;
-mod direction;
-mod io;
-mod iter;
-mod tree;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Duration;
 
-extern crate itertools;
+extern crate expedition;
+
+use expedition::days::{day10, day7};
+use expedition::io::Input;
+use expedition::solver::Part;
+use expedition::{answers, bench, days, runner};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if !(args.len() > 2) {
-        eprintln!("Call must at least specify a day.");
+    if args.len() < 2 {
+        eprintln!(
+            "Call must at least specify a day, 'run-all', 'bench', 'shell', 'cleanup', 'diff', \
+             'debug' or 'crt'."
+        );
+        process::exit(2);
+    }
+
+    let command: &str = &args[1];
+    let options = Options::parse(&args[2..]).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(2);
+    });
+
+    match command {
+        "run-all" => run_all(&options),
+        "bench" => run_bench(&options),
+        "shell" => run_shell(&options),
+        "cleanup" => run_cleanup(&options),
+        "diff" => run_diff(&options),
+        "debug" => run_debug(&options),
+        "crt" => run_crt(&options),
+        day => run_day(day, &options),
+    }
+}
+
+#[derive(PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Flags and positional arguments following the day or subcommand
+struct Options {
+    format: Format,
+    check: bool,
+    render: bool,
+    bench: bench::Config,
+    baseline: Option<String>,
+    save_baseline: Option<String>,
+    threshold: f64,
+    cleanup: day7::cleanup::Config,
+    crt: day10::crt::Config,
+    backend: day10::crt::Backend,
+    scale: usize,
+    animate: bool,
+    positional: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            format: Format::Text,
+            check: false,
+            render: false,
+            bench: bench::Config::default(),
+            baseline: None,
+            save_baseline: None,
+            threshold: bench::THRESHOLD,
+            cleanup: day7::cleanup::Config::default(),
+            crt: day10::crt::Config::default(),
+            backend: day10::crt::Backend::Ascii,
+            scale: 1,
+            animate: false,
+            positional: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--check" => options.check = true,
+                "--render" => options.render = true,
+                "--format" => {
+                    options.format = match args.next().map(|s| s.as_str()) {
+                        Some("text") => Format::Text,
+                        Some("json") => Format::Json,
+                        other => {
+                            return Err(format!(
+                                "'--format' expects 'text' or 'json', got {:?}",
+                                other
+                            ))
+                        }
+                    }
+                }
+                "--runs" => options.bench.runs = parse_value(arg, args.next())?,
+                "--warmup" => options.bench.warmup = parse_value(arg, args.next())?,
+                "--threshold" => options.threshold = parse_value(arg, args.next())?,
+                "--baseline" => options.baseline = Some(parse_value(arg, args.next())?),
+                "--save-baseline" => options.save_baseline = Some(parse_value(arg, args.next())?),
+                "--disk-size" => options.cleanup.disk_size = parse_value(arg, args.next())?,
+                "--free" => options.cleanup.required_free = parse_value(arg, args.next())?,
+                "--protect" => options
+                    .cleanup
+                    .protected
+                    .push(parse_value(arg, args.next())?),
+                "--objective" => {
+                    options.cleanup.objective = match args.next().map(|s| s.as_str()) {
+                        Some("smallest") => day7::cleanup::Objective::SmallestTotal,
+                        Some("fewest") => day7::cleanup::Objective::FewestDirs,
+                        other => {
+                            return Err(format!(
+                                "'--objective' expects 'smallest' or 'fewest', got {:?}",
+                                other
+                            ))
+                        }
+                    }
+                }
+                "--width" => options.crt.width = parse_value(arg, args.next())?,
+                "--height" => options.crt.height = parse_value(arg, args.next())?,
+                "--sprite" => options.crt.sprite_width = parse_value(arg, args.next())?,
+                "--backend" => options.backend = parse_value(arg, args.next())?,
+                "--scale" => options.scale = parse_value(arg, args.next())?,
+                "--animate" => options.animate = true,
+                flag if flag.starts_with("--") => return Err(format!("Unknown flag: {flag}")),
+                _ => options.positional.push(arg.clone()),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn parse_value<T>(flag: &str, value: Option<&String>) -> Result<T, String>
+where
+    T: std::str::FromStr,
+{
+    let value = value.ok_or_else(|| format!("'{flag}' expects a value"))?;
+    value
+        .parse::<T>()
+        .map_err(|_| format!("Invalid value for '{flag}': {value}"))
+}
+
+fn run_day(day: &str, options: &Options) {
+    if options.positional.len() != 1 {
+        eprintln!("Call must specify exactly one input file for {day}, or '-' for stdin.");
+        process::exit(2);
+    }
+    if options.check {
+        eprintln!("'--check' is only supported by 'run-all'.");
+        process::exit(2);
+    }
+
+    let solver = days::find(day).unwrap_or_else(|| {
+        eprintln!("Not a valid day: {day}");
+        process::exit(2);
+    });
+
+    let input = Input::from_arg(&options.positional[0]).unwrap_or_else(|e| {
+        eprintln!("Could not read input: {e}");
+        process::exit(2);
+    });
+
+    if options.render {
+        match solver.render(&input) {
+            Some(Ok(rendered)) => print!("{rendered}"),
+            Some(Err(e)) => {
+                eprintln!("{} could not render: {}", solver.name(), e);
+                process::exit(1);
+            }
+            None => {
+                eprintln!("{} has nothing to render.", solver.name());
+                process::exit(2);
+            }
+        }
+        return;
+    }
+
+    let result = runner::run_day(solver.as_ref(), &input);
+    match options.format {
+        Format::Json => print!("{}", runner::json_lines(&[result])),
+        Format::Text => {
+            let mut failed = false;
+            for (part_result, part) in result.parts.iter().zip(Part::iter()) {
+                match &part_result.answer {
+                    Ok(answer) => println!("{}", solver.describe(*part, answer)),
+                    Err(msg) => {
+                        eprintln!("{} {} failed: {}", result.name, part, msg);
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Runs every day against its input in the given directory, or `files/` by default.
+/// With `--check`, the answers are verified against the manifest in that directory.
+fn run_all(options: &Options) {
+    if options.positional.len() > 1 {
+        eprintln!("'run-all' takes at most one argument, the input directory.");
+        process::exit(2);
+    }
+    let input_dir = options
+        .positional
+        .first()
+        .map_or(runner::INPUT_DIR, |s| s.as_str());
+
+    let results = runner::run_all(&days::registry(), input_dir);
+    match options.format {
+        Format::Text => print!("{}", runner::summary_table(&results)),
+        Format::Json => print!("{}", runner::json_lines(&results)),
+    }
+
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|r| !r.is_ok())
+        .map(|r| r.name.as_str())
+        .collect();
+    if !failed.is_empty() {
+        eprintln!("Failed days: {}", failed.join(", "));
+    }
+
+    let mut mismatched = false;
+    if options.check {
+        let manifest_path = Path::new(input_dir).join(answers::MANIFEST);
+        let manifest = answers::Manifest::load(&manifest_path).unwrap_or_else(|e| {
+            eprintln!("Could not load answers: {e}");
+            process::exit(2);
+        });
+        if options.format == Format::Text {
+            println!();
+        }
+        for verdict in results.iter().flat_map(|r| manifest.check(r)) {
+            mismatched |= !verdict.is_ok();
+            // keep stdout machine readable
+            match options.format {
+                Format::Text => println!("{}", verdict),
+                Format::Json => eprintln!("{}", verdict),
+            }
+        }
+    }
+
+    if !failed.is_empty() || mismatched {
+        process::exit(1);
+    }
+}
+
+/// Solves one day, or all days, repeatedly against their inputs and reports timing statistics.
+/// The medians can be saved as a baseline, and compared against one to flag regressions.
+fn run_bench(options: &Options) {
+    if options.positional.is_empty() || options.positional.len() > 2 {
+        eprintln!("'bench' takes a day or 'all', and optionally the input directory.");
+        process::exit(2);
+    }
+    let solvers = match options.positional[0].as_str() {
+        "all" => days::registry(),
+        day => vec![days::find(day).unwrap_or_else(|| {
+            eprintln!("Not a valid day: {day}");
+            process::exit(2);
+        })],
+    };
+    let input_dir = options
+        .positional
+        .get(1)
+        .map_or(runner::INPUT_DIR, |s| s.as_str());
+
+    let results: Vec<bench::DayBench> = solvers
+        .iter()
+        .map(|solver| {
+            let input = Input::file(Path::new(input_dir).join(solver.input()));
+            bench::bench_day(solver.as_ref(), &input, options.bench)
+        })
+        .collect();
+    println!(
+        "{} warm-up and {} measured runs per part",
+        options.bench.warmup, options.bench.runs
+    );
+    print!("{}", bench::stats_table(&results));
+    let failed = results.iter().any(|r| r.parts.iter().any(|p| p.is_err()));
+
+    let mut regressed = false;
+    if let Some(path) = &options.baseline {
+        let baseline = bench::Baseline::load(path).unwrap_or_else(|e| {
+            eprintln!("Could not load baseline: {e}");
+            process::exit(2);
+        });
+        println!();
+        for comparison in bench::compare(&results, &baseline) {
+            let regression = comparison.is_regression(options.threshold);
+            regressed |= regression;
+            println!(
+                "{} {}: {:.3} ms -> {:.3} ms ({:+.1}%){}",
+                comparison.day,
+                comparison.part,
+                comparison.baseline_ms,
+                comparison.current_ms,
+                comparison.change(),
+                if regression { " REGRESSION" } else { "" }
+            );
+        }
+    }
+
+    if let Some(path) = &options.save_baseline {
+        if let Err(e) = bench::Baseline::from_results(&results).save(path) {
+            eprintln!("Could not save baseline: {e}");
+            process::exit(2);
+        }
+    }
+
+    if failed || regressed {
+        process::exit(1);
+    }
+}
+
+/// Reconstructs day7's filesystem from a terminal log and explores it with shell commands
+/// read from stdin, e.g. piped from a script. Exits with status 1 if any command failed.
+fn run_shell(options: &Options) {
+    if options.positional.len() != 1 || options.positional[0] == "-" {
+        eprintln!("'shell' takes the terminal log file; commands are read from stdin.");
+        process::exit(2);
+    }
+    let (fs, warnings) = day7::load_filesystem_with_warnings(&Input::file(&options.positional[0]))
+        .unwrap_or_else(|e| {
+            eprintln!("Could not load the filesystem: {e}");
+            process::exit(2);
+        });
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let mut shell = day7::shell::Shell::new(fs);
+    run_repl("Shell", |input, out, err, prompt| {
+        shell.run(input, out, err, prompt)
+    });
+}
+
+/// Runs an interactive tool on stdin, with a prompt on a terminal, and exits with 1 if
+/// any of its commands failed
+fn run_repl<F>(name: &str, run: F)
+where
+    F: FnOnce(io::StdinLock, &mut io::Stdout, &mut io::Stderr, bool) -> io::Result<usize>,
+{
+    let stdin = io::stdin();
+    let prompt = stdin.is_terminal();
+    let failed =
+        run(stdin.lock(), &mut io::stdout(), &mut io::stderr(), prompt).unwrap_or_else(|e| {
+            eprintln!("{name} aborted: {e}");
+            process::exit(2);
+        });
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+/// Plans which of day7's directories to delete to free enough space, printed as `rm -rf` lines
+fn run_cleanup(options: &Options) {
+    if options.positional.len() != 1 {
+        eprintln!("'cleanup' takes the terminal log file, or '-' for stdin.");
+        process::exit(2);
+    }
+    let input = Input::from_arg(&options.positional[0]).unwrap_or_else(|e| {
+        eprintln!("Could not read input: {e}");
+        process::exit(2);
+    });
+    let (fs, warnings) = day7::load_filesystem_with_warnings(&input).unwrap_or_else(|e| {
+        eprintln!("Could not load the filesystem: {e}");
+        process::exit(2);
+    });
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    match day7::cleanup::plan(&fs, &options.cleanup) {
+        Ok(plan) => print!("{plan}"),
+        Err(e) => {
+            eprintln!("No cleanup plan: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Compares the filesystems of two of day7's terminal logs, taken at different times
+fn run_diff(options: &Options) {
+    if options.positional.len() != 2 {
+        eprintln!("'diff' takes the old and the new terminal log file.");
+        process::exit(2);
+    }
+    let load = |path: &str| {
+        let (fs, warnings) = day7::load_filesystem_with_warnings(&Input::file(path))
+            .unwrap_or_else(|e| {
+                eprintln!("Could not load the filesystem: {e}");
+                process::exit(2);
+            });
+        for warning in warnings {
+            eprintln!("warning: {warning}");
+        }
+        fs
+    };
+    let (old, new) = (load(&options.positional[0]), load(&options.positional[1]));
+
+    let changes = day7::diff::diff(&old, &new);
+    match options.format {
+        Format::Text => print!("{}", day7::diff::text(&changes)),
+        Format::Json => print!("{}", day7::diff::json_lines(&changes)),
+    }
+}
+
+/// Steps through a day10 program with debugger commands read from stdin, e.g. piped from
+/// a script. Exits with status 1 if any command failed.
+fn run_debug(options: &Options) {
+    if options.positional.len() != 1 || options.positional[0] == "-" {
+        eprintln!("'debug' takes the program file; commands are read from stdin.");
+        process::exit(2);
+    }
+    let cpu = day10::Cpu::load(&Input::file(&options.positional[0])).unwrap_or_else(|e| {
+        eprintln!("Could not load the program: {e}");
+        process::exit(2);
+    });
+
+    let mut debugger = day10::debugger::Debugger::new(cpu);
+    run_repl("Debugger", |input, out, err, prompt| {
+        debugger.run(input, out, err, prompt)
+    });
+}
+
+/// Pause between the frames of an animation on a terminal
+const FRAME_DELAY: Duration = Duration::from_millis(25);
+
+/// Draws the image of a day10 program on a CRT of any size, rendered by one of several
+/// backends to stdout or the given file. `--animate` shows the beam drawing cycle by cycle.
+fn run_crt(options: &Options) {
+    if options.positional.is_empty() || options.positional.len() > 2 {
+        eprintln!("'crt' takes the program file, or '-' for stdin, and optionally an output file.");
+        process::exit(2);
+    }
+    if options.scale == 0 || (options.animate && !options.backend.is_text()) {
+        eprintln!("'--scale' must be positive, and only text backends can be animated.");
+        process::exit(2);
+    }
+    let cpu = Input::from_arg(&options.positional[0]).and_then(|input| day10::Cpu::load(&input));
+    let mut cpu = cpu.unwrap_or_else(|e| {
+        eprintln!("Could not load the program: {e}");
+        process::exit(2);
+    });
+    let mut crt = day10::Crt::new(&options.crt).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(2);
+    });
+
+    let drawn = if options.animate {
+        let terminal = io::stdout().is_terminal();
+        crt.animate(&mut cpu, |crt, x| {
+            let frame = crt.frame(options.backend, x);
+            if terminal {
+                // redraw in place
+                print!("\x1b[H\x1b[2J{frame}");
+                thread::sleep(FRAME_DELAY);
+            } else {
+                println!("{frame}");
+            }
+        })
+    } else {
+        crt.draw_program(&mut cpu)
+    };
+    if let Err(e) = drawn {
+        eprintln!("The program failed: {e}");
+        process::exit(1);
+    }
+    if options.animate {
         return;
     }
 
-    let day: &str = &args[1];
-    let args = &args[2..];
-
-    match day {
-        "day1" => days::day1::day1(args),
-        "day2" => days::day2::day2(args),
-        "day3" => days::day3::day3(args),
-        "day4" => days::day4::day4(args),
-        "day5" => days::day5::day5(args),
-        "day6" => days::day6::day6(args),
-        "day7" => days::day7::day7(args),
-        "day8" => days::day8::day8(args),
-        "day9" => days::day9::day9(args),
-        "day10" => days::day10::day10(args),
-        "day11" => days::day11::day11(args),
-        "day12" => days::day12::day12(args),
-        _ => eprintln!("Not a valid day: {day}"),
+    let rendered = match crt.scaled(options.scale) {
+        Ok(scaled) => scaled.render(options.backend),
+        Err(e) => {
+            eprintln!("Could not scale the image: {e}");
+            process::exit(2);
+        }
+    };
+    match options.positional.get(1) {
+        Some(path) => {
+            if let Err(e) = fs::write(path, rendered) {
+                eprintln!("Could not write {path}: {e}");
+                process::exit(2);
+            }
+        }
+        None => print!("{rendered}"),
     }
 }
@@ -0,0 +1,53 @@
+//! A read-eval-print loop for the interactive tools, such as day7's shell and day10's
+//! debugger.
+
+use error::Result;
+use std::io::{self, BufRead, Write};
+
+/// Executes the commands of `input` on `state` line by line until `exit` or the end of
+/// the input, and writes their output to `out`. Failing commands are reported on `err`
+/// and do not stop the loop. With a `prompt`, it is shown before every line, e.g. for a
+/// terminal. Returns the number of failed commands.
+pub fn run<S, R, W, E, P, X>(
+    state: &mut S,
+    input: R,
+    out: &mut W,
+    err: &mut E,
+    prompt: Option<P>,
+    mut execute: X,
+) -> io::Result<usize>
+where
+    R: BufRead,
+    W: Write,
+    E: Write,
+    P: Fn(&S) -> String,
+    X: FnMut(&mut S, &str) -> Result<String>,
+{
+    let mut failed = 0;
+    let mut lines = input.lines();
+    loop {
+        if let Some(prompt) = &prompt {
+            write!(out, "{}", prompt(state))?;
+            out.flush()?;
+        }
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        let line = line.trim();
+        if line == "exit" {
+            break;
+        }
+        match execute(state, line) {
+            Ok(output) => out.write_all(output.as_bytes())?,
+            Err(e) => {
+                failed += 1;
+                writeln!(err, "{}", e)?;
+            }
+        }
+    }
+    if prompt.is_some() {
+        writeln!(out)?;
+    }
+    Ok(failed)
+}
@@ -0,0 +1,243 @@
+use io::Input;
+use serde::Serialize;
+use serde_json;
+use solver::{Answer, Part, Solver};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Default directory holding the puzzle inputs
+pub const INPUT_DIR: &str = "files";
+
+/// Outcome of solving one part of a day
+pub struct PartResult {
+    pub answer: Result<Answer, String>,
+    pub elapsed: Duration,
+}
+
+/// Outcome of solving both parts of a day, in the order of `Part::iter`
+pub struct DayResult {
+    pub day: u32,
+    pub name: String,
+    pub parts: Vec<PartResult>,
+}
+
+/// Machine readable record of one solved part
+#[derive(Serialize)]
+pub struct Record<'a> {
+    pub day: u32,
+    pub part: u32,
+    pub answer: Option<&'a Answer>,
+    pub answer_type: Option<&'static str>,
+    pub elapsed_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'a str>,
+}
+
+impl DayResult {
+    pub fn is_ok(&self) -> bool {
+        self.parts.iter().all(|p| p.answer.is_ok())
+    }
+
+    pub fn records(&self) -> Vec<Record<'_>> {
+        self.parts
+            .iter()
+            .zip(Part::iter())
+            .map(|(result, part)| Record {
+                day: self.day,
+                part: part.number(),
+                answer: result.answer.as_ref().ok(),
+                answer_type: result.answer.as_ref().ok().map(|a| a.type_name()),
+                elapsed_ms: result.elapsed.as_secs_f64() * 1000.0,
+                error: result.answer.as_ref().err().map(|e| e.as_str()),
+            })
+            .collect()
+    }
+}
+
+/// Solves a single part, turning an error or panic inside the solver into a message
+pub fn run_part(solver: &dyn Solver, part: Part, input: &Input) -> PartResult {
+    let start = Instant::now();
+    let answer = match panic::catch_unwind(AssertUnwindSafe(|| solver.solve(part, input))) {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(payload) => Err(panic_message(payload)),
+    };
+    PartResult {
+        answer,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Solves both parts of a day
+pub fn run_day(solver: &dyn Solver, input: &Input) -> DayResult {
+    DayResult {
+        day: solver.day(),
+        name: solver.name(),
+        parts: Part::iter()
+            .map(|part| run_part(solver, *part, input))
+            .collect(),
+    }
+}
+
+/// Solves every registered day against its conventional input in `input_dir`
+pub fn run_all<P>(solvers: &[Box<dyn Solver>], input_dir: P) -> Vec<DayResult>
+where
+    P: AsRef<Path>,
+{
+    solvers
+        .iter()
+        .map(|solver| {
+            let input = Input::file(input_dir.as_ref().join(solver.input()));
+            run_day(solver.as_ref(), &input)
+        })
+        .collect()
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Renders the results as JSON lines, one record per part
+pub fn json_lines(results: &[DayResult]) -> String {
+    let mut lines = String::new();
+    for record in results.iter().flat_map(|r| r.records()) {
+        lines.push_str(&serde_json::to_string(&record).expect("Records are always serializable"));
+        lines.push('\n');
+    }
+    lines
+}
+
+pub fn format_duration(d: Duration) -> String {
+    format!("{:.3} ms", d.as_secs_f64() * 1000.0)
+}
+
+/// Renders the results as a table with one row per day.
+/// Multi-line answers continue on the following lines of their column.
+pub fn summary_table(results: &[DayResult]) -> String {
+    let header: Vec<String> = ["Day", "Part 1", "Time", "Part 2", "Time"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut rows: Vec<Vec<String>> = vec![header];
+    for result in results {
+        let mut cells: Vec<Vec<String>> = vec![vec![result.name.clone()]];
+        for part in result.parts.iter() {
+            let answer = match &part.answer {
+                Ok(answer) => answer.to_string(),
+                Err(msg) => format!("FAILED: {}", msg),
+            };
+            cells.push(answer.lines().map(|l| l.to_string()).collect());
+            cells.push(vec![format_duration(part.elapsed)]);
+        }
+        let height = cells.iter().map(|c| c.len()).max().unwrap_or(1);
+        for line in 0..height {
+            rows.push(
+                cells
+                    .iter()
+                    .map(|c| c.get(line).cloned().unwrap_or_default())
+                    .collect(),
+            );
+        }
+    }
+
+    render_table(&rows)
+}
+
+/// Aligns the cells of the rows into columns, the first row being the header.
+/// Rows may be shorter than the header.
+pub fn render_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.first().map_or(0, |header| header.len());
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            rows.iter()
+                .filter_map(|row| row.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut table = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        table.push_str(line.join(" | ").trim_end());
+        table.push('\n');
+        if i == 0 {
+            let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            table.push_str(&rule.join("-|-"));
+            table.push('\n');
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::Result;
+
+    struct Failing;
+    impl Solver for Failing {
+        fn day(&self) -> u32 {
+            0
+        }
+        fn input(&self) -> &'static str {
+            "nothing.txt"
+        }
+        fn part1(&self, _input: &Input) -> Result<Answer> {
+            Ok(Answer::from("first\nsecond"))
+        }
+        fn part2(&self, _input: &Input) -> Result<Answer> {
+            panic!("no solution")
+        }
+    }
+
+    #[test]
+    fn test_run_day_catches_panics() {
+        let result = run_day(&Failing, &Input::text(""));
+        assert!(!result.is_ok());
+        assert_eq!(Ok(Answer::from("first\nsecond")), result.parts[0].answer);
+        assert_eq!(Err("no solution".to_string()), result.parts[1].answer);
+    }
+
+    #[test]
+    fn test_summary_table_multiline() {
+        let result = run_day(&Failing, &Input::text(""));
+        let table = summary_table(&[result]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(4, lines.len());
+        assert!(lines[0].starts_with("Day"));
+        assert!(lines[2].starts_with("day0 | first "));
+        assert!(lines[2].contains("FAILED: no solution"));
+        assert!(lines[3].trim_start().starts_with("| second"));
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let mut result = run_day(&Failing, &Input::text(""));
+        result.parts[0].elapsed = Duration::from_millis(2);
+        result.parts[1].elapsed = Duration::from_millis(1);
+        let json = json_lines(&[result]);
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(
+            r#"{"day":0,"part":1,"answer":"first\nsecond","answer_type":"text","elapsed_ms":2.0}"#,
+            lines[0]
+        );
+        assert_eq!(
+            r#"{"day":0,"part":2,"answer":null,"answer_type":null,"elapsed_ms":1.0,"error":"no solution"}"#,
+            lines[1]
+        );
+    }
+}
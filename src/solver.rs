@@ -0,0 +1,130 @@
+use error::Result;
+use io::Input;
+use serde::Serialize;
+use std::fmt;
+
+/// The two halves of every puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn iter() -> std::slice::Iter<'static, Part> {
+        static PARTS: [Part; 2] = [Part::One, Part::Two];
+        PARTS.iter()
+    }
+    pub fn number(&self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "part{}", self.number())
+    }
+}
+
+/// Typed result of solving one part of a day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+}
+
+impl Answer {
+    /// Name of the answer type, as reported in machine readable output
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Answer::Int(_) => "int",
+            Answer::UInt(_) => "uint",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::UInt(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Int(n.into())
+    }
+}
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Int(n)
+    }
+}
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::UInt(n.into())
+    }
+}
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::UInt(n)
+    }
+}
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::UInt(n as u64)
+    }
+}
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A solution to one day of the advent calendar.
+pub trait Solver {
+    /// Day of the calendar this solver belongs to, starting at 1
+    fn day(&self) -> u32;
+
+    /// File name of the conventional puzzle input, relative to the input directory
+    fn input(&self) -> &'static str;
+
+    fn part1(&self, input: &Input) -> Result<Answer>;
+
+    fn part2(&self, input: &Input) -> Result<Answer>;
+
+    /// Human readable sentence presenting the answer to a part
+    fn describe(&self, part: Part, answer: &Answer) -> String {
+        format!("Answer to {} {}: {}", self.name(), part, answer)
+    }
+
+    fn name(&self) -> String {
+        format!("day{}", self.day())
+    }
+
+    /// Optional visualization of what the solver reconstructed from the input
+    fn render(&self, _input: &Input) -> Option<Result<String>> {
+        None
+    }
+
+    fn solve(&self, part: Part, input: &Input) -> Result<Answer> {
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+    }
+}
@@ -0,0 +1,816 @@
+use error::Error as CrateError;
+use std::collections::VecDeque;
+use std::error;
+use std::fmt;
+use std::io::{self, Write};
+use std::iter;
+use std::ops::{Index, IndexMut};
+
+mod serialize;
+
+/// Handle of a node within its `Tree`, also serving as the node's unique id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Node<T> {
+    pub name: String,
+    pub value: T,
+    id: NodeId,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+impl<T> Node<T> {
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+impl<T> PartialEq for Node<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> fmt::Display for Node<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.value)
+    }
+}
+
+#[derive(Debug)]
+pub enum NodeError {
+    Add(String),
+    NotFound(String),
+}
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeError::Add(msg) => write!(f, "Could not add node: {}", msg),
+            NodeError::NotFound(msg) => write!(f, "Node not found: {}", msg),
+        }
+    }
+}
+impl error::Error for NodeError {}
+impl From<NodeError> for CrateError {
+    fn from(err: NodeError) -> Self {
+        match err {
+            NodeError::Add(msg) => CrateError::invalid_state(msg),
+            NodeError::NotFound(msg) => CrateError::not_found(msg),
+        }
+    }
+}
+
+/// Tree of named nodes, stored in an arena and addressed by `NodeId`.
+/// Children are kept in insertion order and have unique names among their siblings.
+/// Ids of removed nodes are never reused.
+#[derive(Debug, Clone)]
+pub struct Tree<T> {
+    nodes: Vec<Option<Node<T>>>,
+}
+
+/// Separator of the names in a path, e.g. `/a/e/i`
+pub const SEPARATOR: char = '/';
+
+/// Fails for names that could not be told apart from a path: empty ones, `.`, `..` and
+/// those containing the separator
+fn check_name(name: &str) -> Result<(), NodeError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(SEPARATOR) {
+        return Err(NodeError::Add(format!("'{}' is not a valid name", name)));
+    }
+    Ok(())
+}
+
+impl<T> Tree<T> {
+    /// Creates a tree consisting of only its root
+    pub fn new(root_name: &str, value: T) -> Self {
+        Tree {
+            nodes: vec![Some(Node {
+                name: root_name.to_string(),
+                value,
+                id: NodeId(0),
+                parent: None,
+                children: Vec::new(),
+            })],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// Number of nodes, including the root
+    pub fn len(&self) -> usize {
+        self.nodes.iter().flatten().count()
+    }
+
+    /// A tree always has its root, so it is never empty
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Adds a child named `name` below `parent`
+    pub fn add(&mut self, parent: NodeId, name: &str, value: T) -> Result<NodeId, NodeError> {
+        self.check_contains(parent)?;
+        check_name(name)?;
+        if self.get_child(parent, name).is_some() {
+            return Err(NodeError::Add(format!(
+                "'{}' already exists under node '{}'",
+                name, self[parent].name
+            )));
+        }
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Some(Node {
+            name: name.to_string(),
+            value,
+            id,
+            parent: Some(parent),
+            children: Vec::new(),
+        }));
+        self[parent].children.push(id);
+        Ok(id)
+    }
+
+    /// The node with this id, unless it was removed
+    pub fn get(&self, id: NodeId) -> Option<&Node<T>> {
+        self.nodes.get(id.0)?.as_ref()
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.get(id).is_some()
+    }
+
+    fn check_contains(&self, id: NodeId) -> Result<(), NodeError> {
+        if !self.contains(id) {
+            return Err(NodeError::NotFound(format!("node {} was removed", id)));
+        }
+        Ok(())
+    }
+
+    /// The child of `node` named `child_name`, if both exist
+    pub fn get_child(&self, node: NodeId, child_name: &str) -> Option<NodeId> {
+        self.get(node)?
+            .children
+            .iter()
+            .find(|child| self[**child].name == child_name)
+            .copied()
+    }
+
+    pub fn down(&self, node: NodeId, name: &str) -> Result<NodeId, NodeError> {
+        self.check_contains(node)?;
+        self.get_child(node, name)
+            .ok_or_else(|| NodeError::NotFound(format!("'{}' not found", name)))
+    }
+
+    /// The parent of `node`, unless it is the root or was removed
+    pub fn up(&self, node: NodeId) -> Option<NodeId> {
+        self.get(node)?.parent
+    }
+
+    pub fn value(&self, node: NodeId) -> Result<&T, NodeError> {
+        self.check_contains(node)?;
+        Ok(&self[node].value)
+    }
+
+    pub fn set_value(&mut self, node: NodeId, value: T) -> Result<(), NodeError> {
+        self.check_contains(node)?;
+        self[node].value = value;
+        Ok(())
+    }
+
+    /// Follows a path of names separated by `/`. Absolute paths start at the root, relative
+    /// ones at `from`. `.` stays at the current node and `..` goes up to the parent.
+    pub fn resolve(&self, from: NodeId, path: &str) -> Result<NodeId, NodeError> {
+        self.check_contains(from)?;
+        let mut node = if path.starts_with(SEPARATOR) {
+            self.root()
+        } else {
+            from
+        };
+        for name in path.split(SEPARATOR) {
+            node = match name {
+                "" | "." => node,
+                ".." => self.up(node).ok_or_else(|| {
+                    NodeError::NotFound(format!("'{}' goes above the root", path))
+                })?,
+                _ => self
+                    .get_child(node, name)
+                    .ok_or_else(|| NodeError::NotFound(format!("'{}' not found", path)))?,
+            };
+        }
+        Ok(node)
+    }
+
+    /// Like `resolve`, but creates every missing node on the way with a clone of `value`,
+    /// as `mkdir -p` does
+    pub fn create_path(&mut self, from: NodeId, path: &str, value: T) -> Result<NodeId, NodeError>
+    where
+        T: Clone,
+    {
+        self.check_contains(from)?;
+        let mut node = if path.starts_with(SEPARATOR) {
+            self.root()
+        } else {
+            from
+        };
+        for name in path.split(SEPARATOR) {
+            node = match name {
+                "" | "." => node,
+                ".." => self.up(node).ok_or_else(|| {
+                    NodeError::NotFound(format!("'{}' goes above the root", path))
+                })?,
+                _ => match self.get_child(node, name) {
+                    Some(child) => child,
+                    None => self.add(node, name, value.clone())?,
+                },
+            };
+        }
+        Ok(node)
+    }
+
+    /// Absolute path of a node, `/` for the root
+    pub fn path(&self, node: NodeId) -> Result<String, NodeError> {
+        self.check_contains(node)?;
+        let mut names: Vec<&str> = Vec::new();
+        let mut current = node;
+        while let Some(parent) = self.up(current) {
+            names.push(&self[current].name);
+            current = parent;
+        }
+        names.reverse();
+        Ok(format!("{}{}", SEPARATOR, names.join(&SEPARATOR.to_string())))
+    }
+
+    /// Whether `node` lies in the subtree of `ancestor`, including `ancestor` itself
+    pub fn is_descendant(&self, node: NodeId, ancestor: NodeId) -> bool {
+        let mut current = Some(node);
+        while let Some(n) = current {
+            if n == ancestor {
+                return true;
+            }
+            current = self.up(n);
+        }
+        false
+    }
+
+    fn check_not_root(&self, node: NodeId, action: &str) -> Result<NodeId, NodeError> {
+        self.check_contains(node)?;
+        self.up(node).ok_or_else(|| {
+            NodeError::NotFound(format!(
+                "the root '{}' cannot be {}",
+                self[node].name, action
+            ))
+        })
+    }
+
+    /// Removes a node with its whole subtree and returns the node's value
+    pub fn remove(&mut self, node: NodeId) -> Result<T, NodeError> {
+        let parent = self.check_not_root(node, "removed")?;
+        self[parent].children.retain(|child| *child != node);
+        let subtree: Vec<NodeId> = self.postorder(node).map(|visit| visit.id).collect();
+        let mut value = None;
+        for id in subtree {
+            value = self.nodes[id.0].take().map(|removed| removed.value);
+        }
+        Ok(value.expect("The node itself is visited last"))
+    }
+
+    /// Renames a node, which must not clash with its siblings
+    pub fn rename(&mut self, node: NodeId, name: &str) -> Result<(), NodeError> {
+        self.check_contains(node)?;
+        check_name(name)?;
+        if let Some(parent) = self.up(node) {
+            if self
+                .get_child(parent, name)
+                .is_some_and(|other| other != node)
+            {
+                return Err(NodeError::Add(format!(
+                    "'{}' already exists under node '{}'",
+                    name, self[parent].name
+                )));
+            }
+        }
+        self[node].name = name.to_string();
+        Ok(())
+    }
+
+    /// Moves a node with its subtree below `new_parent`
+    pub fn move_subtree(&mut self, node: NodeId, new_parent: NodeId) -> Result<(), NodeError> {
+        let old_parent = self.check_not_root(node, "moved")?;
+        self.check_contains(new_parent)?;
+        if self.is_descendant(new_parent, node) {
+            return Err(NodeError::Add(format!(
+                "'{}' cannot be moved below itself",
+                self.path(node)?
+            )));
+        }
+        if old_parent == new_parent {
+            return Ok(());
+        }
+        if self.get_child(new_parent, &self[node].name).is_some() {
+            return Err(NodeError::Add(format!(
+                "'{}' already exists under node '{}'",
+                self[node].name, self[new_parent].name
+            )));
+        }
+        self[old_parent].children.retain(|child| *child != node);
+        self[new_parent].children.push(node);
+        self[node].parent = Some(new_parent);
+        Ok(())
+    }
+
+    /// Visits the subtree at `start`, every node before its children
+    pub fn preorder(&self, start: NodeId) -> Preorder<'_, T> {
+        Preorder {
+            tree: self,
+            stack: vec![Visit::new(start, 0)],
+            prune: None,
+        }
+    }
+
+    /// Visits the subtree at `start`, every node after its children
+    pub fn postorder(&self, start: NodeId) -> Postorder<'_, T> {
+        Postorder {
+            tree: self,
+            stack: vec![(Visit::new(start, 0), 0)],
+            prune: None,
+        }
+    }
+
+    /// Visits the subtree at `start` breadth-first, level by level
+    pub fn levelorder(&self, start: NodeId) -> Levelorder<'_, T> {
+        Levelorder {
+            tree: self,
+            queue: vec![Visit::new(start, 0)].into(),
+            prune: None,
+        }
+    }
+
+    /// Calls `f` in postorder on every node of the subtree at `start` that satisfies `p`.
+    /// `f` may modify the tree, e.g. to aggregate the values of a node's children.
+    pub fn traverse_apply_if<P, F>(&mut self, start: NodeId, mut p: P, mut f: F)
+    where
+        P: FnMut(&Node<T>) -> bool,
+        F: FnMut(&mut Tree<T>, NodeId),
+    {
+        let order: Vec<NodeId> = self.postorder(start).map(|visit| visit.id).collect();
+        for node in order {
+            if p(&self[node]) {
+                f(self, node);
+            }
+        }
+    }
+}
+
+impl<T> Index<NodeId> for Tree<T> {
+    type Output = Node<T>;
+
+    fn index(&self, id: NodeId) -> &Node<T> {
+        self.get(id)
+            .unwrap_or_else(|| panic!("node {} was removed", id))
+    }
+}
+
+impl<T> IndexMut<NodeId> for Tree<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut Node<T> {
+        self.nodes
+            .get_mut(id.0)
+            .and_then(|node| node.as_mut())
+            .unwrap_or_else(|| panic!("node {} was removed", id))
+    }
+}
+
+/// A node reached by a traversal, with its depth below the start of the traversal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Visit {
+    pub id: NodeId,
+    pub depth: usize,
+}
+
+impl Visit {
+    fn new(id: NodeId, depth: usize) -> Self {
+        Visit { id, depth }
+    }
+}
+
+/// Predicate deciding which nodes are yielded without descending into their children
+type Prune<'a, T> = Option<Box<dyn FnMut(&Node<T>) -> bool + 'a>>;
+
+fn is_pruned<T>(prune: &mut Prune<'_, T>, node: &Node<T>) -> bool {
+    prune.as_mut().is_some_and(|p| p(node))
+}
+
+/// Children of `visit`, one level deeper
+fn child_visits<T>(tree: &Tree<T>, visit: Visit) -> impl DoubleEndedIterator<Item = Visit> + '_ {
+    tree[visit.id]
+        .children
+        .iter()
+        .map(move |child| Visit::new(*child, visit.depth + 1))
+}
+
+pub struct Preorder<'a, T> {
+    tree: &'a Tree<T>,
+    stack: Vec<Visit>,
+    prune: Prune<'a, T>,
+}
+
+impl<'a, T> Preorder<'a, T> {
+    /// Skips the descendants of every node for which `p` is true; the node itself is still visited
+    pub fn prune<P>(mut self, p: P) -> Self
+    where
+        P: FnMut(&Node<T>) -> bool + 'a,
+    {
+        self.prune = Some(Box::new(p));
+        self
+    }
+}
+
+impl<'a, T> Iterator for Preorder<'a, T> {
+    type Item = Visit;
+
+    fn next(&mut self) -> Option<Visit> {
+        let visit = self.stack.pop()?;
+        if !is_pruned(&mut self.prune, &self.tree[visit.id]) {
+            self.stack.extend(child_visits(self.tree, visit).rev());
+        }
+        Some(visit)
+    }
+}
+
+pub struct Postorder<'a, T> {
+    tree: &'a Tree<T>,
+    /// Nodes on the path from the start, with the index of their next child to descend into
+    stack: Vec<(Visit, usize)>,
+    prune: Prune<'a, T>,
+}
+
+impl<'a, T> Postorder<'a, T> {
+    /// Skips the descendants of every node for which `p` is true; the node itself is still visited
+    pub fn prune<P>(mut self, p: P) -> Self
+    where
+        P: FnMut(&Node<T>) -> bool + 'a,
+    {
+        self.prune = Some(Box::new(p));
+        self
+    }
+}
+
+impl<'a, T> Iterator for Postorder<'a, T> {
+    type Item = Visit;
+
+    fn next(&mut self) -> Option<Visit> {
+        loop {
+            let (visit, next_child) = *self.stack.last()?;
+            let node = &self.tree[visit.id];
+            let descend = next_child < node.children.len()
+                && (next_child > 0 || !is_pruned(&mut self.prune, node));
+            if descend {
+                self.stack.last_mut().unwrap().1 += 1;
+                let child = Visit::new(node.children[next_child], visit.depth + 1);
+                self.stack.push((child, 0));
+            } else {
+                self.stack.pop();
+                return Some(visit);
+            }
+        }
+    }
+}
+
+pub struct Levelorder<'a, T> {
+    tree: &'a Tree<T>,
+    queue: VecDeque<Visit>,
+    prune: Prune<'a, T>,
+}
+
+impl<'a, T> Levelorder<'a, T> {
+    /// Skips the descendants of every node for which `p` is true; the node itself is still visited
+    pub fn prune<P>(mut self, p: P) -> Self
+    where
+        P: FnMut(&Node<T>) -> bool + 'a,
+    {
+        self.prune = Some(Box::new(p));
+        self
+    }
+}
+
+impl<'a, T> Iterator for Levelorder<'a, T> {
+    type Item = Visit;
+
+    fn next(&mut self) -> Option<Visit> {
+        let visit = self.queue.pop_front()?;
+        if !is_pruned(&mut self.prune, &self.tree[visit.id]) {
+            self.queue.extend(child_visits(self.tree, visit));
+        }
+        Some(visit)
+    }
+}
+
+/// Renders the subtree at `start` like the `tree` command: every node on its own line, in
+/// preorder, formatted by its `Display` and connected to its parent with `├──`/`└──`.
+/// Nodes deeper than `max_depth` below `start` are left out.
+pub fn write_tree<T, W>(
+    out: &mut W,
+    tree: &Tree<T>,
+    start: NodeId,
+    max_depth: Option<usize>,
+) -> fmt::Result
+where
+    T: fmt::Display,
+    W: fmt::Write,
+{
+    // for every ancestor level, whether more siblings follow below it
+    let mut open: Vec<bool> = Vec::new();
+    // stop descending at `max_depth`, which the nodes only know by their ancestors
+    let visits = tree.preorder(start).prune(move |node| {
+        max_depth.is_some_and(|max| {
+            let depth = iter::successors(Some(node.id()), |id| tree.up(*id))
+                .take_while(|id| *id != start)
+                .count();
+            depth >= max
+        })
+    });
+    for visit in visits {
+        let node = &tree[visit.id];
+        if visit.depth > 0 {
+            open.truncate(visit.depth - 1);
+            for more in open.iter() {
+                out.write_str(if *more { "│   " } else { "    " })?;
+            }
+            let siblings = tree[node.parent.expect("Only the root has no parent")].children();
+            let is_last = siblings.last() == Some(&visit.id);
+            out.write_str(if is_last { "└── " } else { "├── " })?;
+            open.push(!is_last);
+        }
+        writeln!(out, "{}", node)?;
+    }
+    Ok(())
+}
+
+/// Writes the rendering of `write_tree` to an `io::Write`, e.g. stdout
+pub fn print_tree<T, W>(
+    out: &mut W,
+    tree: &Tree<T>,
+    start: NodeId,
+    max_depth: Option<usize>,
+) -> io::Result<()>
+where
+    T: fmt::Display,
+    W: Write,
+{
+    out.write_all(tree.render(start, max_depth).as_bytes())
+}
+
+impl<T> Tree<T>
+where
+    T: fmt::Display,
+{
+    /// Renders the subtree at `start` as described by `write_tree`
+    pub fn render(&self, start: NodeId, max_depth: Option<usize>) -> String {
+        let mut rendered = String::new();
+        write_tree(&mut rendered, self, start, max_depth).expect("Writing to a String cannot fail");
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Tree<u32> {
+        let mut tree = Tree::new("/", 0);
+        let a = tree.add(tree.root(), "a", 1).unwrap();
+        tree.add(a, "e", 2).unwrap();
+        tree.add(tree.root(), "b", 3).unwrap();
+        tree
+    }
+
+    fn names<I>(tree: &Tree<u32>, visits: I) -> Vec<String>
+    where
+        I: Iterator<Item = Visit>,
+    {
+        visits
+            .map(|visit| format!("{}{}", tree[visit.id].name, visit.depth))
+            .collect()
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut tree = example();
+        let root = tree.root();
+        let a = tree.down(root, "a").unwrap();
+        let e = tree.down(a, "e").unwrap();
+        assert_eq!(Some(a), tree.up(e));
+        assert_eq!(None, tree.up(root));
+        assert!(tree.down(a, "x").is_err());
+        assert!(tree.add(root, "a", 9).is_err());
+
+        tree.set_value(e, 7).unwrap();
+        assert_eq!(&7, tree.value(e).unwrap());
+        assert_eq!(4, tree.len());
+    }
+
+    #[test]
+    fn test_postorder() {
+        let mut tree = example();
+        let root = tree.root();
+        assert_eq!(
+            vec!["e2", "a1", "b1", "/0"],
+            names(&tree, tree.postorder(root))
+        );
+
+        // sum up the values of all subtrees
+        tree.traverse_apply_if(
+            root,
+            |node| !node.is_leaf(),
+            |tree, node| {
+                let sum: u32 = tree[node].children().iter().map(|c| tree[*c].value).sum();
+                tree[node].value += sum;
+            },
+        );
+        assert_eq!(&6, tree.value(root).unwrap());
+    }
+
+    #[test]
+    fn test_tree_is_send() {
+        fn assert_send<S: Send>() {}
+        assert_send::<Tree<String>>();
+    }
+
+    #[test]
+    fn test_traversal_orders() {
+        let mut tree = example();
+        let b = tree.down(tree.root(), "b").unwrap();
+        tree.add(b, "f", 4).unwrap();
+        let root = tree.root();
+
+        assert_eq!(
+            vec!["/0", "a1", "e2", "b1", "f2"],
+            names(&tree, tree.preorder(root))
+        );
+        assert_eq!(
+            vec!["e2", "a1", "f2", "b1", "/0"],
+            names(&tree, tree.postorder(root))
+        );
+        assert_eq!(
+            vec!["/0", "a1", "b1", "e2", "f2"],
+            names(&tree, tree.levelorder(root))
+        );
+        assert_eq!(vec!["b0", "f1"], names(&tree, tree.preorder(b)));
+    }
+
+    #[test]
+    fn test_single_node_pruning_and_early_termination() {
+        let leaf = Tree::new("leaf", 0);
+        let only = vec![Visit::new(leaf.root(), 0)];
+        assert_eq!(only, leaf.preorder(leaf.root()).collect::<Vec<_>>());
+        assert_eq!(only, leaf.postorder(leaf.root()).collect::<Vec<_>>());
+        assert_eq!(only, leaf.levelorder(leaf.root()).collect::<Vec<_>>());
+
+        let tree = example();
+        let root = tree.root();
+        let skip_a = |node: &Node<u32>| node.name == "a";
+        assert_eq!(
+            vec!["/0", "a1", "b1"],
+            names(&tree, tree.preorder(root).prune(skip_a))
+        );
+        assert_eq!(
+            vec!["a1", "b1", "/0"],
+            names(&tree, tree.postorder(root).prune(skip_a))
+        );
+        assert_eq!(
+            vec!["/0", "a1", "b1"],
+            names(&tree, tree.levelorder(root).prune(skip_a))
+        );
+
+        let first_leaf = tree.preorder(root).find(|visit| tree[visit.id].is_leaf());
+        assert_eq!(
+            Some("e"),
+            first_leaf.map(|visit| tree[visit.id].name.as_str())
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let mut tree = example();
+        let a = tree.down(tree.root(), "a").unwrap();
+        tree.add(a, "g", 5).unwrap();
+        let b = tree.down(tree.root(), "b").unwrap();
+        tree.add(b, "f", 4).unwrap();
+
+        let expected = "\
+/ (0)
+├── a (1)
+│   ├── e (2)
+│   └── g (5)
+└── b (3)
+    └── f (4)
+";
+        assert_eq!(expected, tree.render(tree.root(), None));
+        assert_eq!(
+            "/ (0)\n├── a (1)\n└── b (3)\n",
+            tree.render(tree.root(), Some(1))
+        );
+        assert_eq!("b (3)\n└── f (4)\n", tree.render(b, None));
+
+        let mut out: Vec<u8> = Vec::new();
+        print_tree(&mut out, &tree, b, Some(0)).unwrap();
+        assert_eq!("b (3)\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_paths() {
+        let mut tree = example();
+        let root = tree.root();
+        let e = tree.resolve(root, "/a/e").unwrap();
+        assert_eq!("/a/e", tree.path(e).unwrap());
+        assert_eq!("/", tree.path(root).unwrap());
+        assert_eq!(e, tree.resolve(e, ".").unwrap());
+        assert_eq!(
+            tree.resolve(root, "b").unwrap(),
+            tree.resolve(e, "../../b").unwrap()
+        );
+        assert_eq!(root, tree.resolve(e, "/").unwrap());
+        assert!(matches!(
+            tree.resolve(e, "/a/x"),
+            Err(NodeError::NotFound(_))
+        ));
+        assert!(matches!(
+            tree.resolve(root, ".."),
+            Err(NodeError::NotFound(_))
+        ));
+
+        let i = tree.create_path(e, "f/g/../i", 9).unwrap();
+        assert_eq!("/a/e/f/i", tree.path(i).unwrap());
+        assert_eq!(i, tree.resolve(root, "/a/e/f/i").unwrap());
+        assert!(tree.resolve(root, "/a/e/f/g").is_ok());
+        assert_eq!(e, tree.create_path(root, "/a/e", 0).unwrap());
+        assert_eq!(&2, tree.value(e).unwrap());
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut tree = example();
+        let root = tree.root();
+        let a = tree.resolve(root, "/a").unwrap();
+        let e = tree.resolve(root, "/a/e").unwrap();
+        let b = tree.resolve(root, "/b").unwrap();
+
+        assert!(matches!(tree.rename(a, "b"), Err(NodeError::Add(_))));
+        for name in ["", ".", "..", "c/d"].iter() {
+            assert!(matches!(tree.rename(a, name), Err(NodeError::Add(_))));
+            assert!(matches!(tree.add(a, name, 5), Err(NodeError::Add(_))));
+        }
+        tree.rename(a, "c").unwrap();
+        assert_eq!(e, tree.resolve(root, "/c/e").unwrap());
+
+        assert!(matches!(tree.move_subtree(a, e), Err(NodeError::Add(_))));
+        assert!(tree.move_subtree(root, a).is_err());
+        tree.move_subtree(a, b).unwrap();
+        assert_eq!("/b/c/e", tree.path(e).unwrap());
+        assert_eq!(Some(b), tree.up(a));
+
+        assert_eq!(1, tree.remove(a).unwrap());
+        assert!(!tree.contains(a) && !tree.contains(e));
+        assert_eq!(2, tree.len());
+        assert!(tree.resolve(root, "/b/c").is_err());
+        assert!(matches!(tree.remove(a), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.remove(root), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.rename(a, "d"), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.move_subtree(b, a), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.add(a, "d", 5), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.down(a, "e"), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.value(a), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.set_value(a, 5), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.resolve(a, "e"), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.create_path(a, "d", 5), Err(NodeError::NotFound(_))));
+        assert!(matches!(tree.path(e), Err(NodeError::NotFound(_))));
+        assert_eq!(None, tree.get_child(a, "e"));
+        assert_eq!(None, tree.up(e));
+
+        // ids stay unique after removals
+        let d = tree.add(b, "c", 4).unwrap();
+        assert!(d != a && d != e);
+    }
+}
@@ -0,0 +1,268 @@
+use super::{NodeId, Tree};
+use error::{Error, Result};
+use io::{self, Input};
+use serde::de::DeserializeOwned;
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use serde_json;
+use serde_stacker;
+
+/// Number of spaces per level of the indented text format
+const INDENT: usize = 2;
+
+/// Serializes a subtree as `{"name": .., "value": .., "children": [..]}`
+struct Subtree<'a, T> {
+    tree: &'a Tree<T>,
+    id: NodeId,
+}
+
+impl<'a, T> Serialize for Subtree<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let node = &self.tree[self.id];
+        let children: Vec<Subtree<'_, T>> = node
+            .children()
+            .iter()
+            .map(|child| Subtree {
+                tree: self.tree,
+                id: *child,
+            })
+            .collect();
+        let mut state = serializer.serialize_struct("Node", 3)?;
+        state.serialize_field("name", &node.name)?;
+        state.serialize_field("value", &node.value)?;
+        state.serialize_field("children", &children)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+struct OwnedNode<T> {
+    name: String,
+    value: T,
+    #[serde(default)]
+    children: Vec<OwnedNode<T>>,
+}
+
+impl<T> Tree<T>
+where
+    T: Serialize,
+{
+    /// Serializes the subtree at `start` to pretty printed JSON. Fails for values that JSON
+    /// cannot represent, such as maps with keys other than strings.
+    pub fn to_json(&self, start: NodeId) -> Result<String> {
+        let mut json = Vec::new();
+        let subtree = Subtree {
+            tree: self,
+            id: start,
+        };
+        // deep trees nest deeper than the stack allows without growing it
+        subtree
+            .serialize(serde_stacker::Serializer::new(
+                &mut serde_json::Serializer::pretty(&mut json),
+            ))
+            .map_err(|e| Error::invalid_state(e.to_string()))?;
+        String::from_utf8(json).map_err(|e| Error::invalid_state(e.to_string()))
+    }
+
+    /// Serializes the subtree at `start` to an indented text format: one node per line,
+    /// indented by two spaces per level, with its name and its value as JSON separated
+    /// by a tab, e.g. `"  a\t{\"Dir\":94853}"`. Names starting with a space or containing
+    /// a tab or newline cannot be written.
+    pub fn to_text(&self, start: NodeId) -> Result<String> {
+        let mut text = String::new();
+        for visit in self.preorder(start) {
+            let node = &self[visit.id];
+            if node.name.contains(['\t', '\n']) || node.name.starts_with(' ') {
+                return Err(Error::invalid_state(format!(
+                    "'{}' cannot be written in the text format",
+                    node.name
+                )));
+            }
+            let value = serde_json::to_string(&node.value)
+                .map_err(|e| Error::invalid_state(e.to_string()))?;
+            text.push_str(&" ".repeat(visit.depth * INDENT));
+            text.push_str(&node.name);
+            text.push('\t');
+            text.push_str(&value);
+            text.push('\n');
+        }
+        Ok(text)
+    }
+}
+
+impl<T> Tree<T>
+where
+    T: DeserializeOwned,
+{
+    /// Reads a tree written by `to_json`, however deep
+    pub fn from_json(input: &Input) -> Result<Tree<T>> {
+        let mut json = serde_json::Deserializer::from_reader(input.reader()?);
+        json.disable_recursion_limit();
+        let root = OwnedNode::<T>::deserialize(serde_stacker::Deserializer::new(&mut json))
+            .and_then(|root| json.end().map(|_| root))
+            .map_err(|e| {
+                Error::parse(format!("Invalid tree: {}", message(&e)))
+                    .column(e.column())
+                    .at(&input.name(), e.line())
+            })?;
+        let mut tree = Tree::new(&root.name, root.value);
+        let mut stack: Vec<(NodeId, OwnedNode<T>)> = Vec::new();
+        let top = tree.root();
+        stack.extend(root.children.into_iter().rev().map(|child| (top, child)));
+        while let Some((parent, node)) = stack.pop() {
+            let id = tree.add(parent, &node.name, node.value)?;
+            stack.extend(node.children.into_iter().rev().map(|child| (id, child)));
+        }
+        Ok(tree)
+    }
+
+    /// Reads a tree written by `to_text`
+    pub fn from_text(input: &Input) -> Result<Tree<T>> {
+        let mut tree: Option<Tree<T>> = None;
+        // ancestors of the next node, one per level
+        let mut path: Vec<NodeId> = Vec::new();
+        io::foreach_nonempty_line(input, |line| {
+            let name_start = line.len() - line.trim_start_matches(' ').len();
+            if name_start % INDENT != 0 {
+                return Err(Error::parse(format!(
+                    "Indentation must be a multiple of {} spaces",
+                    INDENT
+                )));
+            }
+            let depth = name_start / INDENT;
+            let (name, value) = line[name_start..]
+                .split_once('\t')
+                .ok_or_else(|| Error::parse("Expected a name and a value separated by a tab"))?;
+            let value: T = serde_json::from_str(value).map_err(|e| {
+                Error::parse(format!("Invalid value: {}", message(&e)))
+                    .column(name_start + name.len() + 1 + e.column())
+            })?;
+
+            match tree.as_mut() {
+                None if depth == 0 => {
+                    let root = Tree::new(name, value);
+                    path.push(root.root());
+                    tree = Some(root);
+                }
+                Some(tree) if depth > 0 && depth <= path.len() => {
+                    path.truncate(depth);
+                    let id = tree.add(path[depth - 1], name, value)?;
+                    path.push(id);
+                }
+                _ => {
+                    return Err(Error::parse(format!(
+                        "Unexpected indentation of {} levels",
+                        depth
+                    )))
+                }
+            }
+            Ok(())
+        })?;
+        tree.ok_or_else(|| Error::parse("Empty tree").at(&input.name(), 1))
+    }
+}
+
+/// The message of a JSON error without the position serde_json appends, which callers
+/// report themselves
+fn message(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    let position = format!(" at line {} column {}", e.line(), e.column());
+    match message.strip_suffix(&position) {
+        Some(message) => message.to_string(),
+        None => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Tree<Option<u32>> {
+        let mut tree = Tree::new("/", None);
+        let a = tree.add(tree.root(), "a b", None).unwrap();
+        tree.add(a, "e.txt", Some(584)).unwrap();
+        tree.add(tree.root(), "c", Some(1)).unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let tree = example();
+        let json = tree.to_json(tree.root()).unwrap();
+        let copy: Tree<Option<u32>> = Tree::from_json(&Input::text(&json)).unwrap();
+        assert_eq!(tree.render_debug(), copy.render_debug());
+        assert_eq!(json, copy.to_json(copy.root()).unwrap());
+
+        let err = Tree::<u32>::from_json(&Input::text("{\"name\": \"x\",\n  \"value\": \"y\"}"))
+            .unwrap_err();
+        assert_eq!(
+            "<text>:2:14: Invalid tree: invalid type: string \"y\", expected u32",
+            err.to_string()
+        );
+
+        let mut keys = std::collections::BTreeMap::new();
+        keys.insert(vec![1], 2);
+        let tree = Tree::new("/", keys);
+        assert!(tree.to_json(tree.root()).is_err());
+    }
+
+    #[test]
+    fn test_deep_json_roundtrip() {
+        let mut tree = Tree::new("/", 0);
+        let mut id = tree.root();
+        for depth in 1..500 {
+            id = tree.add(id, "d", depth).unwrap();
+        }
+        let json = tree.to_json(tree.root()).unwrap();
+        let copy: Tree<u32> = Tree::from_json(&Input::text(&json)).unwrap();
+        assert_eq!(tree.render_debug(), copy.render_debug());
+    }
+
+    #[test]
+    fn test_text_roundtrip() {
+        let tree = example();
+        let text = tree.to_text(tree.root()).unwrap();
+        assert_eq!("/\tnull\n  a b\tnull\n    e.txt\t584\n  c\t1\n", text);
+        let copy: Tree<Option<u32>> = Tree::from_text(&Input::text(&text)).unwrap();
+        assert_eq!(tree.render_debug(), copy.render_debug());
+
+        let a = tree.resolve(tree.root(), "a b").unwrap();
+        assert_eq!("a b\tnull\n  e.txt\t584\n", tree.to_text(a).unwrap());
+
+        let err = Tree::<u32>::from_text(&Input::text("/\t0\n    a\t1\n")).unwrap_err();
+        assert_eq!(
+            "<text>:2: Unexpected indentation of 2 levels",
+            err.to_string()
+        );
+        let err = Tree::<u32>::from_text(&Input::text("/\t0\n  a\tx\n")).unwrap_err();
+        assert!(
+            err.to_string().starts_with("<text>:2:5: Invalid value"),
+            "{}",
+            err
+        );
+        let err = Tree::<u32>::from_text(&Input::text("")).unwrap_err();
+        assert_eq!("<text>:1: Empty tree", err.to_string());
+    }
+
+    impl<T> Tree<T>
+    where
+        T: std::fmt::Debug,
+    {
+        /// Names, values and structure of the whole tree, ignoring ids
+        fn render_debug(&self) -> Vec<String> {
+            self.preorder(self.root())
+                .map(|visit| {
+                    let node = &self[visit.id];
+                    format!("{} {} {:?}", visit.depth, node.name, node.value)
+                })
+                .collect()
+        }
+    }
+}
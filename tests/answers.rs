@@ -0,0 +1,21 @@
+use std::process::Command;
+
+/// Every day must reproduce the answers recorded in `files/answers.toml`
+#[test]
+fn test_all_days_match_answers_manifest() {
+    let output = Command::new(env!("CARGO_BIN_EXE_expedition"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["run-all", "--check"])
+        .output()
+        .expect("Could not run expedition");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "answers differ from the manifest:\n{}\n{}",
+        stdout,
+        stderr
+    );
+    assert!(!stdout.contains("no expected answer"), "{}", stdout);
+}
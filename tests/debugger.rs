@@ -0,0 +1,28 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// The day10 debugger reads its commands from stdin, so scripts can be piped into it
+#[test]
+fn test_debugger_runs_piped_script() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_expedition"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["debug", "files/program.txt"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Could not run expedition");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"break 20\ncontinue\nprint x\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(
+        "breakpoint at cycle 20\ncycle 20  pc 12  noop  x=21\nx=21\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert!(output.status.success());
+}
@@ -0,0 +1,85 @@
+extern crate expedition;
+
+use expedition::algorithm;
+use expedition::days::{self, day10, day7};
+use expedition::io::{self, Input};
+use expedition::iter::window_iter::make_window_iter;
+use expedition::runner;
+use expedition::solver::{Answer, Part};
+use expedition::tree::Tree;
+
+#[test]
+fn test_solve_day_from_text() {
+    let solver = days::find("day6").unwrap();
+    let input = Input::text("mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+    assert_eq!(Answer::UInt(7), solver.solve(Part::One, &input).unwrap());
+
+    let result = runner::run_day(solver.as_ref(), &input);
+    assert!(result.is_ok());
+    assert_eq!(Ok(Answer::UInt(19)), result.parts[1].answer);
+}
+
+#[test]
+fn test_errors_are_reported_not_panicked() {
+    let solver = days::find("day10").unwrap();
+    let err = solver
+        .solve(Part::One, &Input::text("noop\njump 3\n"))
+        .unwrap_err();
+    assert_eq!("<text>:2: Unrecognized command: jump 3", err.to_string());
+}
+
+#[test]
+fn test_io_lines() {
+    let mut total = 0;
+    io::foreach_nonempty_line(&Input::text("1\n\n2\n3\n"), |line| {
+        total += line.parse::<u32>()?;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(6, total);
+}
+
+#[test]
+fn test_window_iter_and_algorithm() {
+    let mut reader = "abacd".as_bytes();
+    let unique: Vec<bool> = make_window_iter::<_, 3>(&mut reader)
+        .unwrap()
+        .map(algorithm::has_only_unique)
+        .collect();
+    assert_eq!(vec![false, true, true], unique);
+}
+
+#[test]
+fn test_tree() {
+    let mut tree = Tree::new("root", 0);
+    let root = tree.root();
+    tree.add(root, "a", 1).unwrap();
+    let a = tree.down(root, "a").unwrap();
+    assert_eq!(&1, tree.value(a).unwrap());
+    assert!(tree.down(root, "b").is_err());
+    assert_eq!("root", tree[tree.up(a).unwrap()].name);
+}
+
+#[test]
+fn test_day_parsers() {
+    assert_eq!(
+        day10::Instruction::Addx(day10::Register::X, -3),
+        day10::parse_instruction("addx -3").unwrap()
+    );
+
+    let input = Input::text("$ cd /\n$ ls\n100 a.txt\ndir d\n$ cd d\n20 b.txt\n");
+    let fs = day7::load_filesystem(&input).unwrap();
+    assert!(fs[fs.root()].value == day7::FsNode::Dir(120));
+}
+
+#[test]
+fn test_day7_snapshot_roundtrip() {
+    let fs = day7::load_filesystem(&Input::file("files/terminal.log")).unwrap();
+    let root = fs.root();
+
+    let json: day7::FsTree = Tree::from_json(&Input::text(&fs.to_json(root).unwrap())).unwrap();
+    assert_eq!(fs.render(root, None), json.render(json.root(), None));
+
+    let text: day7::FsTree = Tree::from_text(&Input::text(&fs.to_text(root).unwrap())).unwrap();
+    assert_eq!(fs.render(root, None), text.render(text.root(), None));
+}
@@ -0,0 +1,29 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// The day7 shell reads its commands from stdin, so scripts can be piped into it
+#[test]
+fn test_shell_runs_piped_script() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_expedition"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["shell", "files/terminal.log"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Could not run expedition");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"cd cmcrzdt/chqllfw\npwd\nls -l\ndu -s /\ncd nowhere\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(
+        "/cmcrzdt/chqllfw\n-  56623 cgs.hbt\n- 134804 zqb.grc\n40208860\t/\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'nowhere' not found"));
+    assert_eq!(Some(1), output.status.code());
+}